@@ -0,0 +1,77 @@
+use std::rc;
+use std::marker;
+
+use super::expression;
+use super::field;
+use super::sql;
+use super::visitor::{self, Visitor, Rewriter};
+
+#[derive(Clone)]
+/// Expression that is rendered as `expr AS alias` in select lists and as `alias` anywhere else.
+pub struct AliasedExpression<T> {
+    expression: expression::SharedExpression,
+    alias: String,
+
+    _marker: marker::PhantomData<T>
+}
+
+impl<T: Clone> AliasedExpression<T> {
+    pub fn new(expression: &expression::Expression<T>, alias: &str) -> AliasedExpression<T> {
+        // Re-aliasing replaces the alias instead of nesting `expr AS a AS b`
+        let expression = match expression.alias_of() {
+            Some((inner, _)) => inner.clone(),
+            None => expression.upcast_expression()
+        };
+
+        AliasedExpression {
+            expression: expression,
+            alias: alias.to_string(),
+
+            _marker: marker::PhantomData
+        }
+    }
+
+    pub fn get_expression(&self) -> &expression::SharedExpression { &self.expression }
+    pub fn get_alias(&self) -> &String { &self.alias }
+
+    /// Typed reference to the alias that can be used in ORDER BY, HAVING, etc.
+    pub fn field(&self) -> field::NamedField<T> {
+        field::NamedField::new(&self.alias, "")
+    }
+}
+
+pub trait ToAliasedExpression<T: Clone>: expression::Expression<T> + Sized {
+    fn as_(&self, alias: &str) -> AliasedExpression<T> {
+        AliasedExpression::new(self, alias)
+    }
+}
+
+impl<T: Clone, E> ToAliasedExpression<T> for E where E: expression::Expression<T> { }
+
+impl<T: Clone + 'static> expression::UntypedExpression for AliasedExpression<T> {
+    fn expression_as_sql(&self) -> &sql::ToSql {
+        self
+    }
+
+    fn upcast_expression(&self) -> expression::SharedExpression {
+        rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
+    }
+
+    fn output_name(&self) -> Option<String> {
+        Some(self.alias.to_string())
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        self.expression.accept_expression(visitor)
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_expression(&mut self.expression, rewriter)
+    }
+
+    fn alias_of(&self) -> Option<(&expression::SharedExpression, &String)> {
+        Some((&self.expression, &self.alias))
+    }
+}
+
+impl<T: Clone + 'static> expression::Expression<T> for AliasedExpression<T> { }
@@ -0,0 +1,125 @@
+use super::from;
+use super::field::{self, Field};
+use super::expression;
+use super::schema::{self, ToColumnType};
+use super::sql;
+
+#[derive(Clone)]
+pub enum Constraint {
+    PrimaryKey(Vec<String>),
+    Unique(Vec<String>),
+    ForeignKey(Vec<String>, String, Vec<String>)
+}
+
+#[derive(Clone)]
+pub enum AlterAction {
+    AddColumn(schema::ColumnDef),
+    DropColumn(String),
+    RenameColumn(String, String),
+    /// Redefines the whole column, MySQL can't change a column partially.
+    ModifyColumn(schema::ColumnDef),
+    SetType(String, schema::ColumnType),
+    SetDefault(String, sql::SharedLiteral),
+    DropDefault(String),
+    SetNotNull(String),
+    DropNotNull(String),
+    AddConstraint(String, Constraint),
+    DropConstraint(String),
+    RenameTo(String)
+}
+
+pub trait Alterable: from::Table + Sized {
+    fn alter(&self) -> AlterTableQuery {
+        AlterTableQuery::new(self)
+    }
+}
+
+fn names(fields: &[&field::Field]) -> Vec<String> {
+    fields.iter().map(|f| f.name().to_string()).collect()
+}
+
+#[derive(Clone)]
+pub struct AlterTableQuery {
+    table: from::SharedTable,
+    actions: Vec<AlterAction>
+}
+
+impl AlterTableQuery {
+    pub fn get_table(&self) -> &from::SharedTable { &self.table }
+    pub fn get_actions(&self) -> &Vec<AlterAction> { &self.actions }
+
+    pub fn new(table: &from::Table) -> AlterTableQuery {
+        AlterTableQuery { table: table.upcast_table(), actions: vec![] }
+    }
+
+    pub fn action(mut self, action: AlterAction) -> AlterTableQuery {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn add_column(self, column: schema::ColumnDef) -> AlterTableQuery {
+        self.action(AlterAction::AddColumn(column))
+    }
+
+    pub fn drop_column(self, field: &field::Field) -> AlterTableQuery {
+        self.action(AlterAction::DropColumn(field.name().to_string()))
+    }
+
+    pub fn rename_column(self, field: &field::Field, name: &str) -> AlterTableQuery {
+        self.action(AlterAction::RenameColumn(field.name().to_string(), name.to_string()))
+    }
+
+    /// Replaces the column definition (type, nullability and default) in one action,
+    /// key and reference constraints of `column` are only applied by MySQL.
+    pub fn modify_column(self, column: schema::ColumnDef) -> AlterTableQuery {
+        self.action(AlterAction::ModifyColumn(column))
+    }
+
+    /// Changes the column type to the one of the field, e.g. pass `NamedField<i64>` to widen an integer.
+    pub fn set_type<T: ToColumnType + Clone + 'static>(self, field: &field::NamedField<T>) -> AlterTableQuery {
+        self.action(AlterAction::SetType(field.name().to_string(), T::column_type()))
+    }
+
+    pub fn set_default<T, V>(self, field: &field::NamedField<T>, value: &V) -> AlterTableQuery
+        where T: Clone + 'static, V: expression::ToExpression<T> + sql::ToSqlLiteral {
+        self.action(AlterAction::SetDefault(field.name().to_string(), value.upcast_literal()))
+    }
+
+    pub fn drop_default(self, field: &field::Field) -> AlterTableQuery {
+        self.action(AlterAction::DropDefault(field.name().to_string()))
+    }
+
+    pub fn set_not_null(self, field: &field::Field) -> AlterTableQuery {
+        self.action(AlterAction::SetNotNull(field.name().to_string()))
+    }
+
+    pub fn drop_not_null(self, field: &field::Field) -> AlterTableQuery {
+        self.action(AlterAction::DropNotNull(field.name().to_string()))
+    }
+
+    pub fn add_primary_key(self, name: &str, fields: &[&field::Field]) -> AlterTableQuery {
+        self.action(AlterAction::AddConstraint(name.to_string(), Constraint::PrimaryKey(names(fields))))
+    }
+
+    pub fn add_unique(self, name: &str, fields: &[&field::Field]) -> AlterTableQuery {
+        self.action(AlterAction::AddConstraint(name.to_string(), Constraint::Unique(names(fields))))
+    }
+
+    /// Referenced fields must belong to one table and match `fields` in number, otherwise rendering fails.
+    pub fn add_foreign_key(self, name: &str, fields: &[&field::Field], references: &[&field::Field]) -> AlterTableQuery {
+        let tables: Vec<String> = references.iter().map(|f| f.table_name().to_string()).collect();
+        let table = match tables.first() {
+            Some(first) if tables.iter().all(|t| t == first) => first.to_string(),
+            _ => String::new()
+        };
+        self.action(AlterAction::AddConstraint(name.to_string(), Constraint::ForeignKey(names(fields), table, names(references))))
+    }
+
+    pub fn drop_constraint(self, name: &str) -> AlterTableQuery {
+        self.action(AlterAction::DropConstraint(name.to_string()))
+    }
+
+    pub fn rename_to(self, name: &str) -> AlterTableQuery {
+        self.action(AlterAction::RenameTo(name.to_string()))
+    }
+}
@@ -4,6 +4,7 @@ use std::marker;
 use super::select_query;
 use super::from;
 use super::predicate;
+use super::order_by;
 
 pub trait Deletable<M>: from::Table + Sized {
     fn delete(&self) -> DeleteQuery<(), select_query::NoResult, M> {
@@ -50,6 +51,8 @@ pub struct DeleteQuery<T, L, M> {
     using: Option<Vec<from::SharedFrom>>,
     where_: Option<predicate::SharedPredicate>,
     returning: Option<select_query::Select>,
+    order_by: Vec<order_by::OrderBy>,
+    limit: Option<usize>,
 
     _marker_t: marker::PhantomData<T>,
     _marker_l: marker::PhantomData<L>,
@@ -64,6 +67,12 @@ impl<T, L, M> DeleteQuery<T, L, M> {
     pub fn get_using(&self) -> &Option<Vec<from::SharedFrom>> { &self.using }
     pub fn get_where(&self) -> &Option<predicate::SharedPredicate> { &self.where_ }
     pub fn get_returning(&self) -> &Option<select_query::Select> { &self.returning }
+    pub fn get_order_by(&self) -> &Vec<order_by::OrderBy> { &self.order_by }
+    pub fn get_limit(&self) -> &Option<usize> { &self.limit }
+
+    pub fn get_using_mut(&mut self) -> &mut Option<Vec<from::SharedFrom>> { &mut self.using }
+    pub fn get_where_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { &mut self.where_ }
+    pub fn get_returning_mut(&mut self) -> &mut Option<select_query::Select> { &mut self.returning }
 
     pub fn new(table: &from::Table) -> DeleteQuery<T, L, M> {
         DeleteQuery {
@@ -73,6 +82,8 @@ impl<T, L, M> DeleteQuery<T, L, M> {
             where_: None,
             all: false,
             returning: None,
+            order_by: vec![],
+            limit: None,
 
             _marker_t: marker::PhantomData,
             _marker_l: marker::PhantomData,
@@ -99,6 +110,11 @@ impl<T, L, M> DeleteQuery<T, L, M> {
         self.all = true;
         self
     }
+
+    pub fn limit(mut self, limit: usize) -> DeleteQuery<T, L, M> {
+        self.limit = Some(limit);
+        self
+    }
 }
 
 returning_for!(DeleteQuery);
@@ -109,3 +125,7 @@ impl<T:Clone, L:Clone, M:Clone> select_query::Queryable for DeleteQuery<T, L, M>
     fn unset_where(&mut self) { self.where_ = None; }
 }
 
+impl<T:Clone, L:Clone, M:Clone> select_query::Orderable for DeleteQuery<T, L, M> {
+    fn get_order_by_mut(&mut self) -> &mut Vec<order_by::OrderBy> { &mut self.order_by }
+    fn set_order_by(&mut self, order_by: Vec<order_by::OrderBy>) { self.order_by = order_by }
+}
@@ -12,6 +12,7 @@ impl Distinct {
     }
 
     pub fn get_on(&self) -> &Option<Vec<expression::SharedExpression>> { &self.on }
+    pub fn get_on_mut(&mut self) -> &mut Option<Vec<expression::SharedExpression>> { &mut self.on }
 
     pub fn on(fields: &[&expression::UntypedExpression]) -> Distinct {
         Distinct { on: Some(
@@ -0,0 +1,98 @@
+/// LRU cache of prepared statements keyed by the final SQL text.
+pub struct StatementCache<S> {
+    capacity: usize,
+    // Least recently used first
+    entries: Vec<(String, S)>,
+    hits: u64,
+    misses: u64
+}
+
+impl<S> StatementCache<S> {
+    pub fn new(capacity: usize) -> StatementCache<S> {
+        StatementCache {
+            capacity: capacity,
+            entries: vec![],
+            hits: 0,
+            misses: 0
+        }
+    }
+
+    pub fn get_capacity(&self) -> usize { self.capacity }
+    pub fn get_hits(&self) -> u64 { self.hits }
+    pub fn get_misses(&self) -> u64 { self.misses }
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn contains(&self, sql: &str) -> bool {
+        self.entries.iter().any(|&(ref cached, _)| cached == sql)
+    }
+
+    /// Cached statement for `sql`, prepared with `prepare` on a miss.
+    ///
+    /// Statements are cloned out of the cache (e.g. `Rc`), so it isn't borrowed while they run.
+    /// With zero capacity nothing is cached.
+    pub fn get_or_prepare<E, F>(&mut self, sql: &str, prepare: F) -> Result<S, E>
+        where S: Clone, F: FnOnce(&str) -> Result<S, E> {
+
+        match self.entries.iter().position(|&(ref cached, _)| cached == sql) {
+            Some(pos) => {
+                self.hits += 1;
+                let entry = self.entries.remove(pos);
+                self.entries.push(entry);
+            },
+            None => {
+                self.misses += 1;
+                let statement = try!(prepare(sql));
+                if self.capacity == 0 {
+                    return Ok(statement)
+                }
+                if self.entries.len() >= self.capacity {
+                    self.entries.remove(0);
+                }
+                self.entries.push((sql.to_string(), statement));
+            }
+        }
+
+        Ok(self.entries.last().unwrap().1.clone())
+    }
+
+    /// Drops all statements, e.g. after the schema was changed.
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Whether the statement changes the schema, so prepared statements may be stale.
+///
+/// Any CREATE, ALTER or DROP keyword counts, wherever it is (e.g. after a comment or a
+/// WITH clause), string literals, quoted identifiers and comments are skipped.
+pub fn is_schema_change(sql: &str) -> bool {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            quote @ '\'' | quote @ '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != quote { i += 1 }
+                i += 1;
+            },
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' { i += 1 }
+            },
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) { i += 1 }
+                i += 2;
+            },
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') { i += 1 }
+                let word: String = chars[start..i].iter().cloned().collect::<String>().to_uppercase();
+                if word == "CREATE" || word == "ALTER" || word == "DROP" {
+                    return true
+                }
+            },
+            _ => i += 1
+        }
+    }
+    false
+}
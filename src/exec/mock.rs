@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+
+use super::super::sql;
+use super::{Executor, ExecError};
+use super::row::{Row, MemoryRow, Value};
+
+/// Statement run by `MockExecutor`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub sql: String,
+    pub values: Vec<Value>
+}
+
+#[derive(Clone)]
+enum MockResult {
+    Rows(Vec<MemoryRow>),
+    Affected(u64),
+    /// SQLSTATE code and the number of times left to fail.
+    Error(String, usize)
+}
+
+/// Executor recording every statement instead of running it.
+///
+/// Results are canned per SQL pattern: the first registered pattern contained
+/// in the rendered SQL wins, statements without a match produce no rows and no affected rows.
+pub struct MockExecutor {
+    adapter: Box<Fn() -> sql::BoxedAdapter>,
+    results: RefCell<Vec<(String, MockResult)>>,
+    statements: RefCell<Vec<Statement>>
+}
+
+impl MockExecutor {
+    pub fn new() -> MockExecutor {
+        MockExecutor::with_adapter(sql::PostgreSqlAdapter)
+    }
+
+    pub fn with_adapter<A: sql::SqlAdapter + Clone + 'static>(adapter: A) -> MockExecutor {
+        MockExecutor {
+            adapter: Box::new(move || -> sql::BoxedAdapter { Box::new(adapter.clone()) }),
+            results: RefCell::new(vec![]),
+            statements: RefCell::new(vec![])
+        }
+    }
+
+    /// Rows returned by queries containing `pattern`.
+    pub fn on(self, pattern: &str, rows: Vec<MemoryRow>) -> MockExecutor {
+        self.results.borrow_mut().push((pattern.to_string(), MockResult::Rows(rows)));
+        self
+    }
+
+    /// Affected rows count of statements containing `pattern`.
+    pub fn on_execute(self, pattern: &str, affected: u64) -> MockExecutor {
+        self.results.borrow_mut().push((pattern.to_string(), MockResult::Affected(affected)));
+        self
+    }
+
+    /// Fails statements containing `pattern` with SQLSTATE `code` the first `times` times.
+    pub fn on_error(self, pattern: &str, code: &str, times: usize) -> MockExecutor {
+        self.results.borrow_mut().push((pattern.to_string(), MockResult::Error(code.to_string(), times)));
+        self
+    }
+
+    pub fn statements(&self) -> Vec<Statement> {
+        self.statements.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.statements.borrow_mut().clear()
+    }
+
+    pub fn assert_count(&self, count: usize) {
+        let statements = self.statements.borrow();
+        if statements.len() != count {
+            panic!("Expected {} statements, got {}: {:?}", count, statements.len(), *statements)
+        }
+    }
+
+    pub fn assert_sql(&self, idx: usize, sql: &str) {
+        let statement = self.statement(idx);
+        if statement.sql != sql {
+            panic!("Statement {} is `{}`, expected `{}`", idx, statement.sql, sql)
+        }
+    }
+
+    pub fn assert_values(&self, idx: usize, values: &[Value]) {
+        let statement = self.statement(idx);
+        if &statement.values[..] != values {
+            panic!("Statement {} has values {:?}, expected {:?}", idx, statement.values, values)
+        }
+    }
+
+    fn statement(&self, idx: usize) -> Statement {
+        match self.statements.borrow().get(idx) {
+            Some(statement) => statement.clone(),
+            None => panic!("Statement {} was not run", idx)
+        }
+    }
+
+    fn record(&self, sql: &str, ctx: &sql::SqlContext) -> Option<MockResult> {
+        self.statements.borrow_mut().push(Statement {
+            sql: sql.to_string(),
+            values: ctx.values().to_vec()
+        });
+
+        let mut results = self.results.borrow_mut();
+        let found = results.iter_mut().find(|&&mut (ref pattern, ref result)| {
+            match result {
+                &MockResult::Error(_, 0) => false,
+                _ => sql.contains(&pattern[..])
+            }
+        });
+
+        match found {
+            Some(&mut (_, MockResult::Error(ref code, ref mut times))) => {
+                *times -= 1;
+                Some(MockResult::Error(code.to_string(), *times))
+            },
+            Some(&mut (_, ref result)) => Some(result.clone()),
+            None => None
+        }
+    }
+}
+
+fn mock_error(code: String) -> ExecError {
+    ExecError::Backend(code, "Mock error".to_string())
+}
+
+impl Executor for MockExecutor {
+    fn context(&self) -> sql::SqlContext {
+        sql::SqlContext::new((self.adapter)())
+    }
+
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+        match self.record(sql, ctx) {
+            Some(MockResult::Affected(affected)) => Ok(affected),
+            Some(MockResult::Rows(rows)) => Ok(rows.len() as u64),
+            Some(MockResult::Error(code, _)) => Err(mock_error(code)),
+            None => Ok(0)
+        }
+    }
+
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+        match self.record(sql, ctx) {
+            Some(MockResult::Rows(rows)) => {
+                for row in rows.iter() {
+                    try!(f(row));
+                }
+                Ok(())
+            },
+            Some(MockResult::Error(code, _)) => Err(mock_error(code)),
+            _ => Ok(())
+        }
+    }
+}
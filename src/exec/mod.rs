@@ -0,0 +1,227 @@
+use std::fmt;
+use std::error;
+#[cfg(feature = "postgres")] use postgres as pg;
+
+#[cfg(feature = "sqlite")] use rusqlite;
+
+use super::select_query;
+use super::insert_query;
+use super::update_query;
+use super::delete_query;
+use super::sql::{self, QueryToSql};
+
+use self::row::{FromRow};
+
+#[macro_use] pub mod row;
+pub mod mock;
+pub mod cache;
+pub mod transaction;
+pub mod template;
+#[cfg(feature = "postgres")] pub mod postgres;
+#[cfg(feature = "sqlite")] pub mod sqlite;
+
+/// Maps cardinality markers of a query to the result of its execution.
+pub trait Cardinality<T> {
+    type Output;
+
+    /// Whether the query produces rows or only an affected rows count.
+    fn returns_rows() -> bool;
+    fn collect(rows: Vec<T>, affected: u64) -> Self::Output;
+}
+
+impl<T> Cardinality<T> for select_query::LimitMany {
+    type Output = Vec<T>;
+
+    fn returns_rows() -> bool { true }
+    fn collect(rows: Vec<T>, _affected: u64) -> Vec<T> { rows }
+}
+
+impl<T> Cardinality<T> for select_query::LimitOne {
+    type Output = Option<T>;
+
+    fn returns_rows() -> bool { true }
+    fn collect(rows: Vec<T>, _affected: u64) -> Option<T> { rows.into_iter().next() }
+}
+
+impl<T> Cardinality<T> for select_query::NoResult {
+    type Output = u64;
+
+    fn returns_rows() -> bool { false }
+    fn collect(_rows: Vec<T>, affected: u64) -> u64 { affected }
+}
+
+/// Insert without RETURNING, before `returning_*` or `no_returning` is chosen.
+impl<T> Cardinality<T> for () {
+    type Output = u64;
+
+    fn returns_rows() -> bool { false }
+    fn collect(_rows: Vec<T>, affected: u64) -> u64 { affected }
+}
+
+#[derive(Debug)]
+pub enum ExecError {
+    Sql(sql::SqlError),
+    /// Row can't be decoded into the result type of the query.
+    Decode(row::RowError),
+    /// Error with SQLSTATE code and message reported by a backend without own error type (e.g. `MockExecutor`).
+    Backend(String, String),
+    #[cfg(feature = "postgres")]
+    Database(pg::error::Error),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error)
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &ExecError::Sql(ref err) => write!(f, "{}", err),
+            &ExecError::Decode(ref err) => write!(f, "Can't decode row: {}", err),
+            &ExecError::Backend(ref code, ref msg) => write!(f, "{} ({})", msg, code),
+            #[cfg(feature = "postgres")]
+            &ExecError::Database(ref err) => write!(f, "{}", err),
+            #[cfg(feature = "sqlite")]
+            &ExecError::Sqlite(ref err) => write!(f, "{}", err)
+        }
+    }
+}
+
+impl error::Error for ExecError {
+    fn description(&self) -> &str {
+        match self {
+            &ExecError::Sql(ref err) => error::Error::description(err),
+            &ExecError::Decode(ref err) => error::Error::description(err),
+            &ExecError::Backend(_, ref msg) => msg,
+            #[cfg(feature = "postgres")]
+            &ExecError::Database(ref err) => error::Error::description(err),
+            #[cfg(feature = "sqlite")]
+            &ExecError::Sqlite(ref err) => error::Error::description(err)
+        }
+    }
+}
+
+impl ExecError {
+    /// SQLSTATE code of errors reported by the database.
+    pub fn sqlstate(&self) -> Option<&str> {
+        match self {
+            &ExecError::Backend(ref code, _) => Some(&code[..]),
+            #[cfg(feature = "postgres")]
+            &ExecError::Database(pg::error::Error::Db(ref err)) => Some(err.code.code()),
+            _ => None
+        }
+    }
+
+    /// Whether the transaction failed on serialization or deadlock and can be rerun.
+    pub fn is_retryable(&self) -> bool {
+        match self.sqlstate() {
+            Some(code) => transaction::RETRYABLE_SQLSTATES.contains(&code),
+            None => false
+        }
+    }
+}
+
+impl From<sql::SqlError> for ExecError {
+    fn from(err: sql::SqlError) -> ExecError {
+        ExecError::Sql(err)
+    }
+}
+
+impl From<row::RowError> for ExecError {
+    fn from(err: row::RowError) -> ExecError {
+        ExecError::Decode(err)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<pg::error::Error> for ExecError {
+    fn from(err: pg::error::Error) -> ExecError {
+        ExecError::Database(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for ExecError {
+    fn from(err: rusqlite::Error) -> ExecError {
+        ExecError::Sqlite(err)
+    }
+}
+
+/// Connection of an execution backend.
+pub trait Executor {
+    /// Context rendering queries in the dialect of the backend.
+    fn context(&self) -> sql::SqlContext;
+
+    /// Runs the statement with values held by `ctx`, returns affected rows count.
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError>;
+
+    /// Runs the statement with values held by `ctx`, calling `f` for every result row.
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&row::Row) -> Result<(), ExecError>) -> Result<(), ExecError>;
+}
+
+/// Runs rendered SQL and collects the result by cardinality `L`.
+pub fn query_sql<T, L>(executor: &Executor, sql: &str, ctx: &sql::SqlContext) -> Result<<L as Cardinality<T>>::Output, ExecError>
+    where T: FromRow, L: Cardinality<T> {
+
+    if !<L as Cardinality<T>>::returns_rows() {
+        let affected = try!(executor.execute(sql, ctx));
+        return Ok(<L as Cardinality<T>>::collect(vec![], affected))
+    }
+
+    let mut result = vec![];
+    try!(executor.query_rows(sql, ctx, &mut |row| {
+        result.push(try!(T::from_row(row)));
+        Ok(())
+    }));
+
+    let affected = result.len() as u64;
+    Ok(<L as Cardinality<T>>::collect(result, affected))
+}
+
+/// Runs a command statement by statement (e.g. `TruncateQuery` emulated on SQLite), returns affected rows.
+pub fn execute_statements(executor: &Executor, query: &QueryToSql) -> Result<u64, ExecError> {
+    let mut ctx = executor.context();
+    let mut affected = 0;
+    for sql in try!(query.try_to_final_statements(&mut ctx)).iter() {
+        affected += try!(executor.execute(sql, &ctx));
+    }
+    Ok(affected)
+}
+
+/// Sized handle to any executor, including trait objects like `pg::GenericConnection`.
+struct ByRef<'a, E: Executor + ?Sized + 'a>(&'a E);
+
+impl<'a, E: Executor + ?Sized> Executor for ByRef<'a, E> {
+    fn context(&self) -> sql::SqlContext {
+        self.0.context()
+    }
+
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+        self.0.execute(sql, ctx)
+    }
+
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&row::Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+        self.0.query_rows(sql, ctx, f)
+    }
+}
+
+/// Typed execution of queries with any backend.
+pub trait Executable<T: FromRow, L: Cardinality<T>>: QueryToSql {
+    fn query<E: Executor + ?Sized>(&self, executor: &E) -> Result<<L as Cardinality<T>>::Output, ExecError> {
+        let mut ctx = executor.context();
+        let sql = try!(self.try_to_final_sql(&mut ctx));
+        query_sql::<T, L>(&ByRef(executor), &sql, &ctx)
+    }
+}
+
+impl<T, L, M> Executable<T, L> for select_query::SelectQuery<T, L, M>
+    where T: FromRow + Clone, L: Cardinality<T> + Clone, M: Clone {}
+
+impl<T, V, M, RT, RL> Executable<RT, RL> for insert_query::InsertQuery<T, V, M, RT, RL>
+    where T: Clone, V: Clone + sql::ToSql, M: Clone, RT: FromRow + Clone, RL: Cardinality<RT> + Clone {}
+
+impl<T, L, M> Executable<T, L> for update_query::UpdateQuery<T, L, M>
+    where T: FromRow + Clone, L: Cardinality<T> + Clone, M: Clone {}
+
+impl<T, L, M> Executable<T, L> for delete_query::DeleteQuery<T, L, M>
+    where T: FromRow + Clone, L: Cardinality<T> + Clone, M: Clone {}
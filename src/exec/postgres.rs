@@ -0,0 +1,297 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::error;
+use serialize::json;
+use time;
+use uuid;
+use postgres as pg;
+use postgres::types::{FromSql, ToSql, Type, SessionInfo};
+
+use super::super::sql;
+use super::{Executor, Executable, Cardinality, ExecError};
+use super::cache::{self, StatementCache};
+use super::row::{Row, RowError, Value, FromRow};
+
+struct PostgresRow<'a, 'b: 'a> {
+    row: &'a pg::rows::Row<'b>
+}
+
+impl<'a, 'b> PostgresRow<'a, 'b> {
+    fn get_opt<T: FromSql>(&self, idx: usize) -> Result<Option<T>, RowError> {
+        match self.row.get_opt(idx) {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(err)) => Err(RowError::Driver(idx, format!("{}", err))),
+            None => Err(RowError::ColumnCount(idx + 1, self.row.len()))
+        }
+    }
+}
+
+macro_rules! pg_value {
+    ($row:expr, $idx:expr, $t:ty, $variant:ident) => (
+        $row.get_opt::<$t>($idx).map(|v| v.map(Value::$variant).unwrap_or(Value::Null))
+    )
+}
+
+macro_rules! pg_array {
+    ($row:expr, $idx:expr, $t:ty, $variant:ident) => (
+        $row.get_opt::<Vec<Option<$t>>>($idx).map(|v| match v {
+            Some(values) => Value::Array(values.into_iter().map(|v| v.map(Value::$variant).unwrap_or(Value::Null)).collect()),
+            None => Value::Null
+        })
+    )
+}
+
+/// NUMERIC decoded from the binary format into its exact text form.
+struct PgNumeric(String);
+
+impl FromSql for PgNumeric {
+    // Digit count, weight of the first digit, sign and display scale, then base 10000 digits
+    fn from_sql(_: &Type, raw: &[u8], _: &SessionInfo) -> Result<PgNumeric, Box<error::Error + Sync + Send>> {
+        let read = |pos: usize| ((raw[pos] as u16) << 8) | raw[pos + 1] as u16;
+        if raw.len() < 8 || raw.len() != 8 + 2 * read(0) as usize {
+            return Err("invalid NUMERIC value".into())
+        }
+
+        let weight = read(2) as i16 as isize;
+        let sign = read(4);
+        let scale = read(6) as usize;
+        let digits: Vec<u16> = (0..read(0) as usize).map(|i| read(8 + 2 * i)).collect();
+        // Digit with weight `w` is at index `weight - w`, missing ones are zeros
+        let digit = |w: isize| -> u16 {
+            let idx = weight - w;
+            if idx >= 0 && (idx as usize) < digits.len() { digits[idx as usize] } else { 0 }
+        };
+
+        if sign == 0xC000 {
+            return Ok(PgNumeric("NaN".to_string()))
+        }
+
+        let mut sql = if sign == 0x4000 { "-".to_string() } else { String::new() };
+        if weight < 0 {
+            sql.push('0');
+        } else {
+            sql.push_str(&format!("{}", digit(weight)));
+            for w in (0..weight).rev() {
+                sql.push_str(&format!("{:04}", digit(w)));
+            }
+        }
+
+        if scale > 0 {
+            let mut fraction = String::new();
+            let mut w = -1;
+            while fraction.len() < scale {
+                fraction.push_str(&format!("{:04}", digit(w)));
+                w -= 1;
+            }
+            fraction.truncate(scale);
+            sql = format!("{}.{}", sql, fraction);
+        }
+
+        Ok(PgNumeric(sql))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty {
+            &Type::Numeric => true,
+            _ => false
+        }
+    }
+}
+
+impl<'a, 'b> Row for PostgresRow<'a, 'b> {
+    fn len(&self) -> usize {
+        self.row.len()
+    }
+
+    fn column_index(&self, name: &str) -> Option<usize> {
+        self.row.columns().iter().position(|c| c.name() == name)
+    }
+
+    fn get_value(&self, idx: usize) -> Result<Value, RowError> {
+        let ty = match self.row.columns().get(idx) {
+            Some(column) => column.type_().clone(),
+            None => return Err(RowError::ColumnCount(idx + 1, self.row.len()))
+        };
+
+        match ty {
+            Type::Bool => pg_value!(self, idx, bool, Bool),
+            Type::Char => pg_value!(self, idx, i8, I8),
+            Type::Int2 => pg_value!(self, idx, i16, I16),
+            Type::Int4 => pg_value!(self, idx, i32, I32),
+            Type::Int8 => pg_value!(self, idx, i64, I64),
+            Type::Float4 => pg_value!(self, idx, f32, F32),
+            Type::Float8 => pg_value!(self, idx, f64, F64),
+            Type::Text | Type::Varchar | Type::Bpchar | Type::Name => pg_value!(self, idx, String, String),
+            Type::Bytea => pg_value!(self, idx, Vec<u8>, Bytes),
+            Type::Json | Type::Jsonb => pg_value!(self, idx, json::Json, Json),
+            Type::Timestamp | Type::Timestamptz => pg_value!(self, idx, time::Timespec, Timespec),
+            Type::Uuid => pg_value!(self, idx, uuid::Uuid, Uuid),
+            Type::Numeric => self.get_opt::<PgNumeric>(idx).map(|v| v.map(|v| Value::Numeric(v.0)).unwrap_or(Value::Null)),
+            Type::BoolArray => pg_array!(self, idx, bool, Bool),
+            Type::Int2Array => pg_array!(self, idx, i16, I16),
+            Type::Int4Array => pg_array!(self, idx, i32, I32),
+            Type::Int8Array => pg_array!(self, idx, i64, I64),
+            Type::Float4Array => pg_array!(self, idx, f32, F32),
+            Type::Float8Array => pg_array!(self, idx, f64, F64),
+            Type::TextArray | Type::VarcharArray | Type::BpcharArray | Type::NameArray => pg_array!(self, idx, String, String),
+            Type::ByteaArray => pg_array!(self, idx, Vec<u8>, Bytes),
+            Type::JsonArray | Type::JsonbArray => pg_array!(self, idx, json::Json, Json),
+            Type::TimestampArray | Type::TimestamptzArray => pg_array!(self, idx, time::Timespec, Timespec),
+            Type::UuidArray => pg_array!(self, idx, uuid::Uuid, Uuid),
+            Type::NumericArray => {
+                self.get_opt::<Vec<Option<PgNumeric>>>(idx).map(|v| match v {
+                    Some(values) => Value::Array(values.into_iter().map(|v| v.map(|v| Value::Numeric(v.0)).unwrap_or(Value::Null)).collect()),
+                    None => Value::Null
+                })
+            },
+            other => Err(RowError::Driver(idx, format!("unsupported column type {}", other)))
+        }
+    }
+}
+
+fn execute(conn: &pg::GenericConnection, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+    let params: Vec<&ToSql> = ctx.data().iter().map(|v| &**v).collect();
+    Ok(try!(conn.execute(sql, &params)))
+}
+
+fn query_rows(conn: &pg::GenericConnection, sql: &str, ctx: &sql::SqlContext,
+              f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+    let params: Vec<&ToSql> = ctx.data().iter().map(|v| &**v).collect();
+    let rows = try!(conn.query(sql, &params));
+    for row in rows.iter() {
+        try!(f(&PostgresRow { row: &row }));
+    }
+    Ok(())
+}
+
+macro_rules! postgres_executor {
+    ($($conn:ty),+) => (
+        $(
+            impl<'a> Executor for $conn {
+                fn context(&self) -> sql::SqlContext {
+                    sql::SqlContext::new(Box::new(sql::PostgreSqlAdapter))
+                }
+
+                fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+                    execute(self, sql, ctx)
+                }
+
+                fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                              f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+                    query_rows(self, sql, ctx, f)
+                }
+            }
+        )+
+    )
+}
+
+postgres_executor!(pg::Connection, pg::transaction::Transaction<'a>, pg::GenericConnection + 'a);
+
+/// Former PostgreSQL-only row decoding, every `FromRow` type qualifies.
+#[deprecated(since = "0.4.0", note = "use FromRow, rows are decoded the same way with every backend")]
+pub trait FromPostgresRow: FromRow {}
+
+#[allow(deprecated)]
+impl<T: FromRow> FromPostgresRow for T {}
+
+/// Former PostgreSQL-only query execution, `Executable::query` accepts the same connections.
+#[deprecated(since = "0.4.0", note = "use Executable, its query accepts any PostgreSQL connection")]
+pub trait PostgresQuery<T: FromRow, L: Cardinality<T>>: Executable<T, L> {}
+
+#[allow(deprecated)]
+impl<T: FromRow, L: Cardinality<T>, Q: Executable<T, L>> PostgresQuery<T, L> for Q {}
+
+/// Connection caching prepared statements of the last `capacity` distinct queries.
+///
+/// Unlike `Connection::prepare_cached`, which keeps every statement for the lifetime of the
+/// connection, the cache is bounded and can be dropped: after schema-changing statements,
+/// which also are never cached, and when a statement went stale because another connection
+/// changed the schema (it is then prepared again once).
+pub struct CachedConnection<'a> {
+    conn: &'a pg::Connection,
+    cache: RefCell<StatementCache<Rc<pg::stmt::Statement<'a>>>>
+}
+
+impl<'a> CachedConnection<'a> {
+    pub fn new(conn: &'a pg::Connection, capacity: usize) -> CachedConnection<'a> {
+        CachedConnection {
+            conn: conn,
+            cache: RefCell::new(StatementCache::new(capacity))
+        }
+    }
+
+    pub fn get_connection(&self) -> &'a pg::Connection { self.conn }
+    pub fn get_hits(&self) -> u64 { self.cache.borrow().get_hits() }
+    pub fn get_misses(&self) -> u64 { self.cache.borrow().get_misses() }
+
+    pub fn invalidate(&self) {
+        self.cache.borrow_mut().invalidate()
+    }
+
+    // The cache is borrowed only while looking up, so statements can run nested queries
+    fn prepare(&self, sql: &str) -> Result<Rc<pg::stmt::Statement<'a>>, ExecError> {
+        let conn = self.conn;
+        Ok(try!(self.cache.borrow_mut().get_or_prepare(sql, |sql| conn.prepare(sql).map(Rc::new))))
+    }
+
+    fn prepare_again(&self, sql: &str) -> Result<Rc<pg::stmt::Statement<'a>>, ExecError> {
+        self.invalidate();
+        self.prepare(sql)
+    }
+}
+
+/// PostgreSQL refuses to run a prepared statement whose result columns were changed by DDL.
+fn is_stale_statement(err: &pg::error::Error) -> bool {
+    match err {
+        &pg::error::Error::Db(ref err) => err.code.code() == "0A000",
+        _ => false
+    }
+}
+
+fn each_row(rows: &pg::rows::Rows, f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+    for row in rows.iter() {
+        try!(f(&PostgresRow { row: &row }));
+    }
+    Ok(())
+}
+
+impl<'a> Executor for CachedConnection<'a> {
+    fn context(&self) -> sql::SqlContext {
+        sql::SqlContext::new(Box::new(sql::PostgreSqlAdapter))
+    }
+
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+        if cache::is_schema_change(sql) {
+            self.invalidate();
+            return execute(self.conn, sql, ctx)
+        }
+
+        let params: Vec<&ToSql> = ctx.data().iter().map(|v| &**v).collect();
+        let stmt = try!(self.prepare(sql));
+        match stmt.execute(&params) {
+            Err(ref err) if is_stale_statement(err) => Ok(try!(try!(self.prepare_again(sql)).execute(&params))),
+            result => Ok(try!(result))
+        }
+    }
+
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+        if cache::is_schema_change(sql) {
+            self.invalidate();
+            return query_rows(self.conn, sql, ctx, f)
+        }
+
+        let params: Vec<&ToSql> = ctx.data().iter().map(|v| &**v).collect();
+        let stmt = try!(self.prepare(sql));
+        let result = stmt.query(&params);
+        match result {
+            Ok(rows) => each_row(&rows, f),
+            Err(ref err) if is_stale_statement(err) => {
+                let stmt = try!(self.prepare_again(sql));
+                let rows = try!(stmt.query(&params));
+                each_row(&rows, f)
+            },
+            Err(err) => Err(From::from(err))
+        }
+    }
+}
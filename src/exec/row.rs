@@ -0,0 +1,365 @@
+use std::fmt;
+use std::error;
+use serialize::json;
+use time;
+use uuid;
+
+pub use super::super::value::{Value, ToValue, ValueType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowError {
+    /// Row has a different number of columns than the decoded type (expected, actual).
+    ColumnCount(usize, usize),
+    /// Column is absent in the row.
+    MissingColumn(String),
+    /// Column value can't be decoded into the type (column, expected, actual).
+    TypeMismatch(usize, &'static str, &'static str),
+    /// Column value is NULL, but the type is not optional.
+    UnexpectedNull(usize),
+    /// Driver failed to read the column.
+    Driver(usize, String)
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &RowError::ColumnCount(expected, actual) => write!(f, "expected {} columns, got {}", expected, actual),
+            &RowError::MissingColumn(ref name) => write!(f, "column {} is missing", name),
+            &RowError::TypeMismatch(column, expected, actual) => write!(f, "column {}: expected {}, got {}", column, expected, actual),
+            &RowError::UnexpectedNull(column) => write!(f, "column {}: unexpected NULL", column),
+            &RowError::Driver(column, ref msg) => write!(f, "column {}: {}", column, msg),
+        }
+    }
+}
+
+impl error::Error for RowError {
+    fn description(&self) -> &str {
+        match self {
+            &RowError::ColumnCount(_, _) => "Wrong number of columns",
+            &RowError::MissingColumn(_) => "Missing column",
+            &RowError::TypeMismatch(_, _, _) => "Column type mismatch",
+            &RowError::UnexpectedNull(_) => "Unexpected NULL",
+            &RowError::Driver(_, ref msg) => msg,
+        }
+    }
+}
+
+/// Result row of any backend.
+pub trait Row {
+    fn len(&self) -> usize;
+    fn column_index(&self, name: &str) -> Option<usize>;
+    fn get_value(&self, idx: usize) -> Result<Value, RowError>;
+
+    fn get<T: FromValue>(&self, idx: usize) -> Result<T, RowError> where Self: Sized {
+        get(self, idx)
+    }
+}
+
+/// Reads a column of any row and decodes it.
+pub fn get<T: FromValue>(row: &Row, idx: usize) -> Result<T, RowError> {
+    if idx >= row.len() {
+        return Err(RowError::ColumnCount(idx + 1, row.len()))
+    }
+    let value = try!(row.get_value(idx));
+    FromValue::from_value(idx, &value)
+}
+
+/// Reads a column of any row by name and decodes it.
+pub fn get_named<T: FromValue>(row: &Row, name: &str) -> Result<T, RowError> {
+    match row.column_index(name) {
+        Some(idx) => get(row, idx),
+        None => Err(RowError::MissingColumn(name.to_string()))
+    }
+}
+
+/// In-memory row, e.g. for tests or backends without own row type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryRow {
+    columns: Vec<String>,
+    values: Vec<Value>
+}
+
+impl MemoryRow {
+    pub fn new(columns: &[&str], values: Vec<Value>) -> MemoryRow {
+        MemoryRow {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            values: values
+        }
+    }
+
+    pub fn get_columns(&self) -> &Vec<String> { &self.columns }
+    pub fn get_values(&self) -> &Vec<Value> { &self.values }
+}
+
+impl Row for MemoryRow {
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    fn get_value(&self, idx: usize) -> Result<Value, RowError> {
+        match self.values.get(idx) {
+            Some(value) => Ok(value.clone()),
+            None => Err(RowError::ColumnCount(idx + 1, self.values.len()))
+        }
+    }
+}
+
+pub trait FromValue: Sized {
+    fn from_value(column: usize, value: &Value) -> Result<Self, RowError>;
+}
+
+macro_rules! from_value {
+    ($t:ty, $variant:ident, $name:expr) => (
+        impl FromValue for $t {
+            fn from_value(column: usize, value: &Value) -> Result<$t, RowError> {
+                match value {
+                    &Value::$variant(ref v) => Ok(v.clone()),
+                    &Value::Null => Err(RowError::UnexpectedNull(column)),
+                    other => Err(RowError::TypeMismatch(column, $name, other.type_name()))
+                }
+            }
+        }
+    )
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    match value {
+        &Value::I8(v) => Some(v as i64),
+        &Value::I16(v) => Some(v as i64),
+        &Value::I32(v) => Some(v as i64),
+        &Value::I64(v) => Some(v),
+        &Value::Numeric(ref v) => numeric_integer(v),
+        _ => None
+    }
+}
+
+// Decimals with a zero fraction (e.g. AVG of integers) are integers too
+fn numeric_integer(v: &str) -> Option<i64> {
+    let mut parts = v.splitn(2, '.');
+    let integer = parts.next().unwrap_or("");
+    match parts.next() {
+        Some(fraction) if !fraction.chars().all(|c| c == '0') => None,
+        _ => integer.parse().ok()
+    }
+}
+
+// Backends with dynamic typing (e.g. SQLite) don't know the exact width of integers,
+// so any integer value is accepted if it fits.
+macro_rules! integer_from_value {
+    ($t:ty, $name:expr) => (
+        impl FromValue for $t {
+            fn from_value(column: usize, value: &Value) -> Result<$t, RowError> {
+                match (value, integer(value)) {
+                    (&Value::Null, _) => Err(RowError::UnexpectedNull(column)),
+                    (_, Some(v)) if v >= <$t>::min_value() as i64 && v <= <$t>::max_value() as i64 => Ok(v as $t),
+                    (other, _) => Err(RowError::TypeMismatch(column, $name, other.type_name()))
+                }
+            }
+        }
+    )
+}
+
+// Values stored as text by backends without a native type
+macro_rules! parsed_from_value {
+    ($t:ty, $variant:ident, $name:expr, $parse:expr) => (
+        impl FromValue for $t {
+            fn from_value(column: usize, value: &Value) -> Result<$t, RowError> {
+                match value {
+                    &Value::$variant(ref v) => Ok(v.clone()),
+                    &Value::Null => Err(RowError::UnexpectedNull(column)),
+                    &Value::String(ref v) => match $parse(v) {
+                        Some(v) => Ok(v),
+                        None => Err(RowError::TypeMismatch(column, $name, "String"))
+                    },
+                    other => Err(RowError::TypeMismatch(column, $name, other.type_name()))
+                }
+            }
+        }
+    )
+}
+
+impl FromValue for bool {
+    fn from_value(column: usize, value: &Value) -> Result<bool, RowError> {
+        match (value, integer(value)) {
+            (&Value::Bool(v), _) => Ok(v),
+            (&Value::Null, _) => Err(RowError::UnexpectedNull(column)),
+            (_, Some(0)) => Ok(false),
+            (_, Some(1)) => Ok(true),
+            (other, _) => Err(RowError::TypeMismatch(column, "bool", other.type_name()))
+        }
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(column: usize, value: &Value) -> Result<f32, RowError> {
+        match value {
+            &Value::F32(v) => Ok(v),
+            &Value::F64(v) => Ok(v as f32),
+            &Value::Numeric(ref v) => v.parse().map_err(|_| RowError::TypeMismatch(column, "f32", "Numeric")),
+            &Value::Null => Err(RowError::UnexpectedNull(column)),
+            other => Err(RowError::TypeMismatch(column, "f32", other.type_name()))
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(column: usize, value: &Value) -> Result<f64, RowError> {
+        match value {
+            &Value::F32(v) => Ok(v as f64),
+            &Value::F64(v) => Ok(v),
+            &Value::Numeric(ref v) => v.parse().map_err(|_| RowError::TypeMismatch(column, "f64", "Numeric")),
+            &Value::Null => Err(RowError::UnexpectedNull(column)),
+            other => Err(RowError::TypeMismatch(column, "f64", other.type_name()))
+        }
+    }
+}
+
+// Decimals are kept exact when read as strings
+impl FromValue for String {
+    fn from_value(column: usize, value: &Value) -> Result<String, RowError> {
+        match value {
+            &Value::String(ref v) | &Value::Numeric(ref v) => Ok(v.clone()),
+            &Value::Null => Err(RowError::UnexpectedNull(column)),
+            other => Err(RowError::TypeMismatch(column, "String", other.type_name()))
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(column: usize, value: &Value) -> Result<Vec<T>, RowError> {
+        match value {
+            &Value::Array(ref values) => values.iter().map(|v| T::from_value(column, v)).collect(),
+            &Value::Null => Err(RowError::UnexpectedNull(column)),
+            other => Err(RowError::TypeMismatch(column, "Array", other.type_name()))
+        }
+    }
+}
+
+integer_from_value!(i8, "i8");
+integer_from_value!(i16, "i16");
+integer_from_value!(i32, "i32");
+integer_from_value!(i64, "i64");
+from_value!(Vec<u8>, Bytes, "Vec<u8>");
+parsed_from_value!(json::Json, Json, "Json", |v: &String| json::Json::from_str(v).ok());
+parsed_from_value!(time::Timespec, Timespec, "Timespec", parse_timespec);
+parsed_from_value!(uuid::Uuid, Uuid, "Uuid", |v: &String| uuid::Uuid::parse_str(v).ok());
+
+/// Text format of timestamps for backends without a native type, keeps nanoseconds.
+pub const TIMESPEC_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S.%fZ";
+
+// Also accepts whole seconds as written before and SQLite's CURRENT_TIMESTAMP
+const TIMESPEC_PARSE_FORMATS: [&'static str; 3] = [TIMESPEC_FORMAT, "%Y-%m-%dT%H:%M:%SZ", "%Y-%m-%d %H:%M:%S"];
+
+fn parse_timespec(v: &String) -> Option<time::Timespec> {
+    TIMESPEC_PARSE_FORMATS.iter()
+        .filter_map(|format| time::strptime(v, format).ok())
+        .next()
+        .map(|tm| tm.to_timespec())
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(column: usize, value: &Value) -> Result<Option<T>, RowError> {
+        match value {
+            &Value::Null => Ok(None),
+            value => FromValue::from_value(column, value).map(Some)
+        }
+    }
+}
+
+/// Decodes a result row into the result type of a query.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self, RowError>;
+}
+
+fn check_len(row: &Row, expected: usize) -> Result<(), RowError> {
+    if row.len() != expected {
+        Err(RowError::ColumnCount(expected, row.len()))
+    } else {
+        Ok(())
+    }
+}
+
+impl FromRow for () {
+    fn from_row(_row: &Row) -> Result<(), RowError> {
+        Ok(())
+    }
+}
+
+// Single values are used by `returning_1`
+macro_rules! value_from_row {
+    ($t:ty) => (
+        impl FromRow for $t {
+            fn from_row(row: &Row) -> Result<$t, RowError> {
+                try!(check_len(row, 1));
+                get(row, 0)
+            }
+        }
+
+        impl FromRow for Option<$t> {
+            fn from_row(row: &Row) -> Result<Option<$t>, RowError> {
+                try!(check_len(row, 1));
+                get(row, 0)
+            }
+        }
+    )
+}
+
+value_from_row!(bool);
+value_from_row!(i8);
+value_from_row!(i16);
+value_from_row!(i32);
+value_from_row!(i64);
+value_from_row!(f32);
+value_from_row!(f64);
+value_from_row!(String);
+value_from_row!(Vec<u8>);
+value_from_row!(json::Json);
+value_from_row!(time::Timespec);
+value_from_row!(uuid::Uuid);
+
+macro_rules! tuple_from_row {
+    ($len:expr, $(($t:ident, $idx:expr)),+) => (
+        impl<$($t: FromValue,)+> FromRow for ($($t,)+) {
+            fn from_row(row: &Row) -> Result<($($t,)+), RowError> {
+                try!(check_len(row, $len));
+                Ok(($(try!(get::<$t>(row, $idx)),)+))
+            }
+        }
+    )
+}
+
+tuple_from_row!(1, (T0, 0));
+tuple_from_row!(2, (T0, 0), (T1, 1));
+tuple_from_row!(3, (T0, 0), (T1, 1), (T2, 2));
+tuple_from_row!(4, (T0, 0), (T1, 1), (T2, 2), (T3, 3));
+tuple_from_row!(5, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4));
+tuple_from_row!(6, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5));
+tuple_from_row!(7, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6));
+tuple_from_row!(8, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6), (T7, 7));
+tuple_from_row!(9, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6), (T7, 7), (T8, 8));
+tuple_from_row!(10, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6), (T7, 7), (T8, 8), (T9, 9));
+tuple_from_row!(11, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6), (T7, 7), (T8, 8), (T9, 9), (T10, 10));
+tuple_from_row!(12, (T0, 0), (T1, 1), (T2, 2), (T3, 3), (T4, 4), (T5, 5), (T6, 6), (T7, 7), (T8, 8), (T9, 9), (T10, 10), (T11, 11));
+
+/// Implements `FromRow` for a struct, reading every listed field from the column with the same name.
+///
+/// ```ignore
+/// struct Jedi { id: i32, name: String, master_id: Option<i32> }
+/// from_row!(Jedi { id, name, master_id });
+/// ```
+#[macro_export]
+macro_rules! from_row {
+    ($s:ident { $($field:ident),+ $(,)* }) => (
+        impl $crate::FromRow for $s {
+            fn from_row(row: &$crate::Row) -> Result<$s, $crate::RowError> {
+                Ok($s {
+                    $($field: try!($crate::exec::row::get_named(row, stringify!($field))),)+
+                })
+            }
+        }
+    )
+}
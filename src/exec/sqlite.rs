@@ -0,0 +1,80 @@
+use time;
+use rusqlite;
+use rusqlite::types::{ToSql, Value as SqliteValue};
+
+use super::super::sql;
+use super::{Executor, ExecError};
+use super::row::{self, Row, MemoryRow, Value};
+
+// SQLite has no native booleans, timestamps, JSON, UUIDs or decimals, see also `row::FromValue`
+fn to_sqlite_value(value: &Value) -> Result<SqliteValue, ExecError> {
+    Ok(match value {
+        &Value::Null => SqliteValue::Null,
+        &Value::Bool(v) => SqliteValue::Integer(if v { 1 } else { 0 }),
+        &Value::I8(v) => SqliteValue::Integer(v as i64),
+        &Value::I16(v) => SqliteValue::Integer(v as i64),
+        &Value::I32(v) => SqliteValue::Integer(v as i64),
+        &Value::I64(v) => SqliteValue::Integer(v),
+        &Value::F32(v) => SqliteValue::Real(v as f64),
+        &Value::F64(v) => SqliteValue::Real(v),
+        &Value::String(ref v) => SqliteValue::Text(v.to_string()),
+        &Value::Bytes(ref v) => SqliteValue::Blob(v.clone()),
+        &Value::Json(ref v) => SqliteValue::Text(v.to_string()),
+        &Value::Timespec(v) => SqliteValue::Text(time::at_utc(v).strftime(row::TIMESPEC_FORMAT).unwrap().to_string()),
+        &Value::Uuid(ref v) => SqliteValue::Text(v.to_string()),
+        &Value::Numeric(ref v) => SqliteValue::Text(v.to_string()),
+        &Value::Array(_) => return Err(ExecError::Sql(sql::SqlError::Unsupported("Arrays are not supported by SQLite".to_string())))
+    })
+}
+
+fn to_sqlite_values(ctx: &sql::SqlContext) -> Result<Vec<SqliteValue>, ExecError> {
+    if ctx.has_opaque_values() {
+        return Err(ExecError::Sql(sql::SqlError::Unsupported("Values held without `hold_value` are not supported by SQLite".to_string())))
+    }
+    ctx.values().iter().map(to_sqlite_value).collect()
+}
+
+fn from_sqlite_value(value: SqliteValue) -> Value {
+    match value {
+        SqliteValue::Null => Value::Null,
+        SqliteValue::Integer(v) => Value::I64(v),
+        SqliteValue::Real(v) => Value::F64(v),
+        SqliteValue::Text(v) => Value::String(v),
+        SqliteValue::Blob(v) => Value::Bytes(v),
+    }
+}
+
+impl Executor for rusqlite::Connection {
+    fn context(&self) -> sql::SqlContext {
+        sql::SqlContext::new(Box::new(sql::SqliteAdapter))
+    }
+
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+        let values = try!(to_sqlite_values(ctx));
+        let params: Vec<&ToSql> = values.iter().map(|v| v as &ToSql).collect();
+        let affected = try!(rusqlite::Connection::execute(self, sql, &params));
+        Ok(affected as u64)
+    }
+
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+        let values = try!(to_sqlite_values(ctx));
+        let params: Vec<&ToSql> = values.iter().map(|v| v as &ToSql).collect();
+
+        let mut stmt = try!(self.prepare(sql));
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let names: Vec<&str> = columns.iter().map(|c| &c[..]).collect();
+
+        let mut rows = try!(stmt.query(&params));
+        while let Some(sqlite_row) = rows.next() {
+            let sqlite_row = try!(sqlite_row);
+            let mut values = vec![];
+            for idx in 0..columns.len() {
+                let value: SqliteValue = try!(sqlite_row.get_checked(idx as i32));
+                values.push(from_sqlite_value(value));
+            }
+            try!(f(&MemoryRow::new(&names, values)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use std::marker::PhantomData;
+
+use super::super::sql;
+use super::{Cardinality, Executable, Executor, ExecError, query_sql};
+use super::row::{FromRow, ToValue, Value};
+
+/// Query rendered once, executed many times with values bound to its placeholders.
+///
+/// Values held by the query itself (e.g. `name.is("Luke".to_string())`) are fixed at
+/// compile time and bound after the placeholder values.
+pub struct PreparedTemplate<T, L> {
+    sql: String,
+    slots: Vec<Option<&'static str>>,
+    fixed: Vec<Value>,
+    _marker: PhantomData<(T, L)>
+}
+
+impl<T: FromRow, L: Cardinality<T>> PreparedTemplate<T, L> {
+    /// Renders `query` in the dialect of `executor`.
+    pub fn compile<Q: Executable<T, L>>(query: &Q, executor: &Executor) -> Result<PreparedTemplate<T, L>, sql::SqlError> {
+        let mut ctx = executor.context();
+        let sql = try!(query.try_to_final_sql(&mut ctx));
+
+        Ok(PreparedTemplate {
+            sql: sql,
+            slots: ctx.get_placeholder_types(),
+            fixed: ctx.values().to_vec(),
+            _marker: PhantomData
+        })
+    }
+
+    pub fn get_sql(&self) -> &str { &self.sql }
+
+    /// Value types of placeholders, `None` for ones accepting any value.
+    pub fn get_slots(&self) -> &[Option<&'static str>] { &self.slots }
+
+    /// Checks `values` against the placeholder slots.
+    pub fn bind(&self, values: &[&ToValue]) -> Result<Vec<Value>, sql::SqlError> {
+        if values.len() != self.slots.len() {
+            return Err(sql::SqlError::InvalidBind(
+                format!("expected {} values, got {}", self.slots.len(), values.len())
+            ))
+        }
+
+        let mut bound = vec![];
+        for (idx, (value, slot)) in values.iter().zip(self.slots.iter()).enumerate() {
+            let value = value.to_value();
+            match (slot, &value) {
+                (&Some(_), &Value::Null) | (&None, _) => (),
+                (&Some(expected), value) if expected != value.type_name() => {
+                    return Err(sql::SqlError::InvalidBind(
+                        format!("placeholder {} expects {}, got {}", idx + 1, expected, value.type_name())
+                    ))
+                },
+                _ => ()
+            }
+            bound.push(value);
+        }
+
+        Ok(bound)
+    }
+
+    pub fn query(&self, executor: &Executor, values: &[&ToValue]) -> Result<<L as Cardinality<T>>::Output, ExecError> {
+        let bound = try!(self.bind(values));
+
+        let mut ctx = executor.context();
+        for (value, slot) in bound.into_iter().zip(self.slots.iter()) {
+            ctx.bind_as(value, *slot);
+        }
+        for value in self.fixed.iter() {
+            ctx.bind(value.clone());
+        }
+
+        query_sql::<T, L>(executor, &self.sql, &ctx)
+    }
+}
@@ -0,0 +1,123 @@
+use std::cell::Cell;
+
+use super::super::sql;
+use super::super::transaction::TransactionOptions;
+use super::{Executor, ExecError};
+use super::row::Row;
+
+/// SQLSTATE codes of failures fixed by rerunning the transaction.
+pub const RETRYABLE_SQLSTATES: &'static [&'static str] = &[
+    "40001", // serialization_failure
+    "40P01", // deadlock_detected
+];
+
+/// Transaction over any executor, nested transactions are savepoints.
+///
+/// Transaction is rolled back when dropped without `commit`.
+pub struct Transaction<'a> {
+    executor: &'a Executor,
+    depth: usize,
+    finished: Cell<bool>
+}
+
+impl<'a> Transaction<'a> {
+    pub fn begin(executor: &'a Executor, options: &TransactionOptions) -> Result<Transaction<'a>, ExecError> {
+        // MySQL sets the isolation level with a separate statement
+        try!(super::execute_statements(executor, options));
+
+        Ok(Transaction { executor: executor, depth: 0, finished: Cell::new(false) })
+    }
+
+    pub fn get_depth(&self) -> usize { self.depth }
+
+    fn savepoint_name(&self) -> String {
+        format!("sp_{}", self.depth)
+    }
+
+    fn run(&self, sql: &str) -> Result<u64, ExecError> {
+        let ctx = self.executor.context();
+        self.executor.execute(sql, &ctx)
+    }
+
+    /// Nested transaction, mapped to `SAVEPOINT`.
+    pub fn transaction<'b>(&'b self) -> Result<Transaction<'b>, ExecError> {
+        let nested = Transaction { executor: self, depth: self.depth + 1, finished: Cell::new(true) };
+        try!(self.run(&format!("SAVEPOINT {};", nested.savepoint_name())));
+        nested.finished.set(false);
+        Ok(nested)
+    }
+
+    pub fn commit(self) -> Result<(), ExecError> {
+        self.finished.set(true);
+        if self.depth == 0 {
+            self.run("COMMIT;").map(|_| ())
+        } else {
+            self.run(&format!("RELEASE SAVEPOINT {};", self.savepoint_name())).map(|_| ())
+        }
+    }
+
+    pub fn rollback(self) -> Result<(), ExecError> {
+        self.finished.set(true);
+        self.rollback_sql()
+    }
+
+    fn rollback_sql(&self) -> Result<(), ExecError> {
+        if self.depth == 0 {
+            self.run("ROLLBACK;").map(|_| ())
+        } else {
+            self.run(&format!("ROLLBACK TO SAVEPOINT {};", self.savepoint_name())).map(|_| ())
+        }
+    }
+}
+
+impl<'a> Drop for Transaction<'a> {
+    fn drop(&mut self) {
+        if !self.finished.get() {
+            let _ = self.rollback_sql();
+        }
+    }
+}
+
+impl<'a> Executor for Transaction<'a> {
+    fn context(&self) -> sql::SqlContext {
+        self.executor.context()
+    }
+
+    fn execute(&self, sql: &str, ctx: &sql::SqlContext) -> Result<u64, ExecError> {
+        self.executor.execute(sql, ctx)
+    }
+
+    fn query_rows(&self, sql: &str, ctx: &sql::SqlContext,
+                  f: &mut FnMut(&Row) -> Result<(), ExecError>) -> Result<(), ExecError> {
+        self.executor.query_rows(sql, ctx, f)
+    }
+}
+
+/// Runs `f` in a transaction committed on success and rolled back on error.
+pub fn transaction<T, F>(executor: &Executor, options: &TransactionOptions, mut f: F) -> Result<T, ExecError>
+    where F: FnMut(&Transaction) -> Result<T, ExecError> {
+
+    let trans = try!(Transaction::begin(executor, options));
+    match f(&trans) {
+        Ok(value) => trans.commit().map(|_| value),
+        Err(err) => {
+            // The original error is more useful than a failed rollback
+            let _ = trans.rollback();
+            Err(err)
+        }
+    }
+}
+
+/// Like `transaction`, but reruns `f` in a new transaction on serialization failures
+/// and deadlocks, at most `attempts` times in total.
+pub fn retry_transaction<T, F>(executor: &Executor, options: &TransactionOptions, attempts: usize, mut f: F) -> Result<T, ExecError>
+    where F: FnMut(&Transaction) -> Result<T, ExecError> {
+
+    let mut attempt = 1;
+    loop {
+        match transaction(executor, options, &mut f) {
+            Err(ref err) if attempt < attempts && err.is_retryable() => attempt += 1,
+            result => return result
+        }
+    }
+}
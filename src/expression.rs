@@ -6,6 +6,7 @@ use uuid;
 
 use super::sql;
 use super::field;
+use super::visitor::{Visitor, Rewriter};
 
 #[derive(Clone)]
 /// Non-checking expression with any content you want.
@@ -25,6 +26,18 @@ impl RawExpression {
 pub trait UntypedExpression {
     fn expression_as_sql(&self) -> &sql::ToSql;
     fn upcast_expression(&self) -> SharedExpression;
+
+    /// Name of the column this expression produces in a result set, if it is known.
+    fn output_name(&self) -> Option<String> { None }
+
+    /// Walks fields, values and subqueries this expression consists of.
+    fn accept_expression(&self, visitor: &mut Visitor);
+
+    /// Rewrites subqueries this expression consists of.
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter);
+
+    /// Aliased expression and its alias, rendered as `expr AS alias` only in select lists.
+    fn alias_of(&self) -> Option<(&SharedExpression, &String)> { None }
 }
 
 pub type BoxedExpression = Box<UntypedExpression + 'static>;
@@ -49,6 +62,14 @@ macro_rules! to_expression {
             fn upcast_expression(&self) -> SharedExpression {
                 rc::Rc::new(Box::new(self.clone()))
             }
+
+            fn accept_expression(&self, visitor: &mut Visitor) {
+                sql::ToPredicateValue::accept_value(self, visitor)
+            }
+
+            fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+                sql::ToPredicateValue::rewrite_value(self, rewriter)
+            }
         }
 
         impl Expression<$t> for $t { }
@@ -313,6 +334,14 @@ impl<T> UntypedExpression for Vec<T> where T: UntypedExpression + sql::ToPredica
     fn upcast_expression(&self) -> SharedExpression {
         rc::Rc::new(Box::new(self.clone()))
     }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        sql::ToPredicateValue::accept_value(self, visitor)
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        sql::ToPredicateValue::rewrite_value(self, rewriter)
+    }
 }
 
 impl<T> ListExpression<T> for Vec<T> where T: UntypedExpression + sql::ToPredicateValue + Clone + 'static {}
@@ -7,6 +7,7 @@ use uuid;
 use super::from;
 use super::sql;
 use super::expression;
+use super::visitor::{Visitor, Rewriter};
 
 pub trait Field {
     fn name(&self) -> &str;
@@ -70,6 +71,12 @@ impl<T: Clone> NamedField<T> {
         field
     }
 
+    pub fn unqual(&self) -> NamedField<T> {
+        let mut field = self.clone();
+        field.qual = None;
+        field
+    }
+
     pub fn qual_for(&self, table: &from::Table) -> NamedField<T> {
         let mut field = self.clone();
         field.qual = table.get_table_alias().as_ref().map(|v| v.to_string());
@@ -85,6 +92,16 @@ impl<T: Clone + 'static> expression::UntypedExpression for NamedField<T> {
     fn upcast_expression(&self) -> expression::SharedExpression {
         rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
     }
+
+    fn output_name(&self) -> Option<String> {
+        Some(self.name.to_string())
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        visitor.visit_field(self)
+    }
+
+    fn rewrite_expression(&mut self, _rewriter: &mut Rewriter) {}
 }
 
 impl<T: Clone + 'static> Field for NamedField<T> {
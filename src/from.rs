@@ -5,11 +5,32 @@ use super::select_query;
 use super::insert_query;
 use super::update_query;
 use super::delete_query;
+use super::truncate_query;
+use super::schema;
+use super::alter_table_query;
+use super::index_query;
 use super::field::{self, Field};
+use super::alias;
+use super::visitor::{self, Visitor, Visitable, Rewriter, Rewritable};
 
 pub trait From {
     fn as_sql(&self) -> &sql::FromToSql;
     fn upcast_from(&self) -> SharedFrom;
+
+    /// Table names and aliases that fields of this source can be attached to.
+    fn from_names(&self) -> Vec<String> { vec![] }
+
+    /// Walks tables and subqueries of this source.
+    fn accept_from(&self, visitor: &mut Visitor);
+
+    /// Rewrites subqueries of this source.
+    fn rewrite_from(&mut self, rewriter: &mut Rewriter);
+
+    /// Table this source refers to directly, `None` for subqueries and functions.
+    fn as_table(&self) -> Option<&Table> { None }
+
+    /// Output columns of this source, `None` if they are unknown (e.g. plain tables).
+    fn column_names(&self) -> Option<Vec<String>> { None }
 }
 
 pub type BoxedFrom = Box<From + 'static>;
@@ -58,6 +79,10 @@ impl TableDef {
         table_def
     }
 
+    pub fn schema(&self) -> schema::TableSchema {
+        schema::TableSchema::new(self)
+    }
+
     // FIXME: Remove after all stuff in insert_query::InsertQuery will be fixed
     insert!(insert_1, (T0, _t0));
 
@@ -89,12 +114,31 @@ impl From for TableDef {
     fn upcast_from(&self) -> SharedFrom {
         rc::Rc::new(Box::new(self.clone()))
     }
+
+    fn from_names(&self) -> Vec<String> {
+        let mut names = vec![self.name.to_string()];
+        names.extend(self.alias.iter().cloned());
+        names
+    }
+
+    fn accept_from(&self, visitor: &mut Visitor) {
+        visitor.visit_table(self)
+    }
+
+    fn rewrite_from(&mut self, _rewriter: &mut Rewriter) {}
+
+    fn as_table(&self) -> Option<&Table> {
+        Some(self)
+    }
 }
 
 impl select_query::Selectable<()> for TableDef {}
 impl insert_query::Insertable<()> for TableDef {}
 impl update_query::Updatable<()> for TableDef {}
 impl delete_query::Deletable<()> for TableDef {}
+impl truncate_query::Truncatable for TableDef {}
+impl alter_table_query::Alterable for TableDef {}
+impl index_query::Indexable for TableDef {}
 
 #[derive(Clone)]
 pub struct FromSelect<T, L, M> {
@@ -102,6 +146,45 @@ pub struct FromSelect<T, L, M> {
     pub alias: String
 }
 
+impl<T, L, M> FromSelect<T, L, M> {
+    /// Typed field of the derived table that refers to an aliased column of the inner query.
+    pub fn field<R: Clone>(&self, expr: &alias::AliasedExpression<R>) -> field::NamedField<R> {
+        field::NamedField::new_qual(expr.get_alias(), &self.alias, &self.alias)
+    }
+
+    fn column<R: Clone>(&self, idx: usize) -> Option<field::NamedField<R>> {
+        let name = match self.select.get_select() {
+            &select_query::Select::Only(ref exprs) => exprs.get(idx).and_then(|e| e.output_name()),
+            &select_query::Select::All => None
+        };
+
+        name.map(|name| field::NamedField::new_qual(&name, &self.alias, &self.alias))
+    }
+}
+
+impl<T: Clone + 'static, L: Clone + 'static, M: Clone + 'static> FromSelect<T, L, M> {
+    pub fn lateral(&self) -> Lateral {
+        Lateral::new(self)
+    }
+}
+
+impl<T1: Clone, L, M> FromSelect<(T1,), L, M> {
+    /// Typed fields of the derived table, `None` if a column has no name (alias it with `as_()`).
+    pub fn fields(&self) -> Option<(field::NamedField<T1>,)> {
+        self.column(0).map(|f1| (f1,))
+    }
+}
+
+impl<T1: Clone, T2: Clone, L, M> FromSelect<(T1, T2), L, M> {
+    /// Typed fields of the derived table, `None` if a column has no name (alias it with `as_()`).
+    pub fn fields(&self) -> Option<(field::NamedField<T1>, field::NamedField<T2>)> {
+        match (self.column(0), self.column(1)) {
+            (Some(f1), Some(f2)) => Some((f1, f2)),
+            _ => None
+        }
+    }
+}
+
 impl<T: Clone + 'static, L: Clone + 'static, M: Clone + 'static> From for FromSelect<T, L, M> {
     fn as_sql(&self) -> &sql::FromToSql {
         self
@@ -110,7 +193,66 @@ impl<T: Clone + 'static, L: Clone + 'static, M: Clone + 'static> From for FromSe
     fn upcast_from(&self) -> SharedFrom {
         rc::Rc::new(Box::new(self.clone()))
     }
+
+    fn from_names(&self) -> Vec<String> {
+        vec![self.alias.to_string()]
+    }
+
+    fn accept_from(&self, visitor: &mut Visitor) {
+        self.select.accept(visitor)
+    }
+
+    fn rewrite_from(&mut self, rewriter: &mut Rewriter) {
+        self.select.rewrite(rewriter)
+    }
+
+    fn column_names(&self) -> Option<Vec<String>> {
+        match self.select.get_select() {
+            &select_query::Select::Only(ref exprs) => exprs.iter().map(|e| e.output_name()).collect(),
+            &select_query::Select::All => None
+        }
+    }
 }
 
 impl<T: Clone + 'static, L: Clone + 'static, M: Clone + 'static> select_query::Selectable<M> for FromSelect<T, L, M> {}
 
+
+#[derive(Clone)]
+/// FROM source that may refer to columns of preceding FROM items.
+pub struct Lateral {
+    from: SharedFrom
+}
+
+impl Lateral {
+    pub fn new(from: &From) -> Lateral {
+        Lateral { from: from.upcast_from() }
+    }
+
+    pub fn get_from(&self) -> &SharedFrom { &self.from }
+}
+
+impl From for Lateral {
+    fn as_sql(&self) -> &sql::FromToSql {
+        self
+    }
+
+    fn upcast_from(&self) -> SharedFrom {
+        rc::Rc::new(Box::new(self.clone()))
+    }
+
+    fn from_names(&self) -> Vec<String> {
+        self.from.from_names()
+    }
+
+    fn accept_from(&self, visitor: &mut Visitor) {
+        self.from.accept_from(visitor)
+    }
+
+    fn rewrite_from(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_from(&mut self.from, rewriter)
+    }
+
+    fn column_names(&self) -> Option<Vec<String>> {
+        self.from.column_names()
+    }
+}
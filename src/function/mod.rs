@@ -1,10 +1,77 @@
 use std::marker;
 use std::rc;
+use serialize::json;
 use time;
 
 use expression;
 use field;
+use order_by;
+use predicate;
+use select_query;
 use sql;
+use visitor::{self, Visitor, Rewriter};
+
+pub use self::table::{
+    TableFunction,
+    generate_series,
+    generate_series_step,
+    unnest,
+    jsonb_array_elements,
+    jsonb_each
+};
+
+pub mod table;
+
+/// DISTINCT, ORDER BY and FILTER (WHERE ...) parts of an aggregate call.
+#[derive(Clone)]
+pub struct AggregateModifiers {
+    pub distinct: bool,
+    pub order_by: Vec<order_by::OrderBy>,
+    pub filter: Option<predicate::SharedPredicate>
+}
+
+impl AggregateModifiers {
+    pub fn new() -> AggregateModifiers {
+        AggregateModifiers { distinct: false, order_by: vec![], filter: None }
+    }
+
+    fn accept(&self, visitor: &mut Visitor) {
+        for order_by in self.order_by.iter() {
+            order_by.get_by().accept_expression(visitor);
+        }
+        if let Some(ref filter) = self.filter {
+            filter.accept_predicate(visitor);
+        }
+    }
+
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_order_by(&mut self.order_by, rewriter);
+        if let Some(ref mut filter) = self.filter {
+            visitor::rewrite_shared_predicate(filter, rewriter);
+        }
+    }
+}
+
+macro_rules! agg_modifiers {
+    ($foo:ident, $foo_arg:ident) => (
+        impl<R: Clone + 'static, T: Clone + 'static, E: $foo_arg<R, T> + 'static> $foo<R, T, E> {
+            pub fn distinct(&self) -> $foo<R, T, E> {
+                with_clone!(self, agg, agg.modifiers.distinct = true)
+            }
+        }
+
+        impl<R: Clone + 'static, T: Clone + 'static, E: $foo_arg<R, T> + 'static> select_query::Orderable for $foo<R, T, E> {
+            fn get_order_by_mut(&mut self) -> &mut Vec<order_by::OrderBy> { &mut self.modifiers.order_by }
+            fn set_order_by(&mut self, order_by: Vec<order_by::OrderBy>) { self.modifiers.order_by = order_by }
+        }
+
+        impl<R: Clone + 'static, T: Clone + 'static, E: $foo_arg<R, T> + 'static> select_query::HasFilter for $foo<R, T, E> {
+            fn get_filter(&self) -> &Option<predicate::SharedPredicate> { &self.modifiers.filter }
+            fn set_filter(&mut self, predicate: predicate::SharedPredicate) { self.modifiers.filter = Some(predicate); }
+            fn unset_filter(&mut self) { self.modifiers.filter = None; }
+        }
+    )
+}
 
 macro_rules! agg_func {
     ($foo:ident, $foo_arg:ident, $foo_low:ident) => (
@@ -17,6 +84,7 @@ macro_rules! agg_func {
         #[derive(Clone)]
         pub struct $foo<R, T, E: $foo_arg<R, T>> {
             pub expression: E,
+            pub modifiers: AggregateModifiers,
 
             _marker_r: marker::PhantomData<R>,
             _marker_t: marker::PhantomData<T>,
@@ -27,6 +95,7 @@ macro_rules! agg_func {
             pub fn new(expr: E) -> $foo<R, T, E> {
                 $foo {
                     expression: expr.clone(),
+                    modifiers: AggregateModifiers::new(),
 
                     _marker_r: marker::PhantomData,
                     _marker_t: marker::PhantomData,
@@ -42,9 +111,21 @@ macro_rules! agg_func {
             fn upcast_expression(&self) -> expression::SharedExpression {
                 rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
             }
+
+            fn accept_expression(&self, visitor: &mut Visitor) {
+                self.expression.accept_expression(visitor);
+                self.modifiers.accept(visitor);
+            }
+
+            fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+                self.expression.rewrite_expression(rewriter);
+                self.modifiers.rewrite(rewriter);
+            }
         }
 
         impl<R: Clone + 'static, T: Clone + 'static, E: $foo_arg<R, T>  + 'static> expression::Expression<R> for $foo<R, T, E> { }
+
+        agg_modifiers!($foo, $foo_arg);
     )
 }
 
@@ -92,8 +173,226 @@ agg_func!(Count, CountArg, count);
 
 impl<T: 'static + expression::PrimitiveType + Clone> CountArg<i64, T> for field::NamedField<T> {}
 
-#[derive(Clone, Copy)]
-pub struct CountAll;
+agg_func!(BoolAnd, BoolAndArg, bool_and);
+
+impl BoolAndArg<bool, bool> for field::BoolField {}
+
+agg_func!(BoolOr, BoolOrArg, bool_or);
+
+impl BoolOrArg<bool, bool> for field::BoolField {}
+
+agg_func!(ArrayAgg, ArrayAggArg, array_agg);
+
+impl<T: 'static + expression::PrimitiveType + Clone> ArrayAggArg<Vec<T>, T> for field::NamedField<T> {}
+
+agg_func!(JsonAgg, JsonAggArg, json_agg);
+
+impl<T: 'static + expression::PrimitiveType + Clone> JsonAggArg<json::Json, T> for field::NamedField<T> {}
+
+agg_func!(Stddev, StddevArg, stddev);
+
+impl StddevArg<f64, i8> for field::I8Field {}
+impl StddevArg<f64, i16> for field::I16Field {}
+impl StddevArg<f64, i32> for field::I32Field {}
+impl StddevArg<f64, i64> for field::I64Field {}
+impl StddevArg<f64, f32> for field::F32Field {}
+impl StddevArg<f64, f64> for field::F64Field {}
+
+agg_func!(Variance, VarianceArg, variance);
+
+impl VarianceArg<f64, i8> for field::I8Field {}
+impl VarianceArg<f64, i16> for field::I16Field {}
+impl VarianceArg<f64, i32> for field::I32Field {}
+impl VarianceArg<f64, i64> for field::I64Field {}
+impl VarianceArg<f64, f32> for field::F32Field {}
+impl VarianceArg<f64, f64> for field::F64Field {}
+
+pub trait StringAggArg<R: Clone + 'static, T: Clone + 'static>: Clone + expression::Expression<T> + 'static {
+    fn string_agg(&self, delimiter: &str) -> StringAgg<R, T, Self> {
+        StringAgg::new(self.clone(), delimiter)
+    }
+}
+
+#[derive(Clone)]
+pub struct StringAgg<R, T, E: StringAggArg<R, T>> {
+    pub expression: E,
+    pub delimiter: String,
+    pub modifiers: AggregateModifiers,
+
+    _marker_r: marker::PhantomData<R>,
+    _marker_t: marker::PhantomData<T>,
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: StringAggArg<R, T> + 'static> StringAgg<R, T, E> {
+    pub fn new(expr: E, delimiter: &str) -> StringAgg<R, T, E> {
+        StringAgg {
+            expression: expr,
+            delimiter: delimiter.to_string(),
+            modifiers: AggregateModifiers::new(),
+
+            _marker_r: marker::PhantomData,
+            _marker_t: marker::PhantomData,
+        }
+    }
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: StringAggArg<R, T> + 'static> expression::UntypedExpression for StringAgg<R, T, E> {
+    fn expression_as_sql(&self) -> &sql::ToSql {
+        self
+    }
+
+    fn upcast_expression(&self) -> expression::SharedExpression {
+        rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        self.expression.accept_expression(visitor);
+        self.modifiers.accept(visitor);
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        self.expression.rewrite_expression(rewriter);
+        self.modifiers.rewrite(rewriter);
+    }
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: StringAggArg<R, T> + 'static> expression::Expression<R> for StringAgg<R, T, E> { }
+
+agg_modifiers!(StringAgg, StringAggArg);
+
+impl StringAggArg<String, String> for field::StringField {}
+
+/// `JSON_OBJECT_AGG(key, value)` building a JSON object out of key/value pairs.
+#[derive(Clone)]
+pub struct JsonObjectAgg {
+    pub key: expression::SharedExpression,
+    pub value: expression::SharedExpression,
+    pub modifiers: AggregateModifiers
+}
+
+pub fn json_object_agg(key: &expression::Expression<String>, value: &expression::UntypedExpression) -> JsonObjectAgg {
+    JsonObjectAgg {
+        key: key.upcast_expression(),
+        value: value.upcast_expression(),
+        modifiers: AggregateModifiers::new()
+    }
+}
+
+impl expression::UntypedExpression for JsonObjectAgg {
+    fn expression_as_sql(&self) -> &sql::ToSql {
+        self
+    }
+
+    fn upcast_expression(&self) -> expression::SharedExpression {
+        rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        self.key.accept_expression(visitor);
+        self.value.accept_expression(visitor);
+        self.modifiers.accept(visitor);
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_expression(&mut self.key, rewriter);
+        visitor::rewrite_shared_expression(&mut self.value, rewriter);
+        self.modifiers.rewrite(rewriter);
+    }
+}
+
+impl expression::Expression<json::Json> for JsonObjectAgg { }
+
+impl select_query::Orderable for JsonObjectAgg {
+    fn get_order_by_mut(&mut self) -> &mut Vec<order_by::OrderBy> { &mut self.modifiers.order_by }
+    fn set_order_by(&mut self, order_by: Vec<order_by::OrderBy>) { self.modifiers.order_by = order_by }
+}
+
+impl select_query::HasFilter for JsonObjectAgg {
+    fn get_filter(&self) -> &Option<predicate::SharedPredicate> { &self.modifiers.filter }
+    fn set_filter(&mut self, predicate: predicate::SharedPredicate) { self.modifiers.filter = Some(predicate); }
+    fn unset_filter(&mut self) { self.modifiers.filter = None; }
+}
+
+/// Ordered-set aggregate, the argument is sorted with `WITHIN GROUP (ORDER BY ...)`.
+pub trait PercentileContArg<R: Clone + 'static, T: Clone + 'static>: Clone + expression::Expression<T> + 'static {
+    fn percentile_cont(&self, fraction: f64) -> PercentileCont<R, T, Self> {
+        PercentileCont::new(self.clone(), fraction)
+    }
+}
+
+#[derive(Clone)]
+pub struct PercentileCont<R, T, E: PercentileContArg<R, T>> {
+    pub fraction: f64,
+    pub within_group: order_by::OrderBy,
+    pub filter: Option<predicate::SharedPredicate>,
+
+    _marker_r: marker::PhantomData<R>,
+    _marker_t: marker::PhantomData<T>,
+    _marker_e: marker::PhantomData<E>,
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: PercentileContArg<R, T> + 'static> PercentileCont<R, T, E> {
+    pub fn new(expr: E, fraction: f64) -> PercentileCont<R, T, E> {
+        PercentileCont {
+            fraction: fraction,
+            within_group: order_by::OrderBy::by(&expr),
+            filter: None,
+
+            _marker_r: marker::PhantomData,
+            _marker_t: marker::PhantomData,
+            _marker_e: marker::PhantomData,
+        }
+    }
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: PercentileContArg<R, T> + 'static> expression::UntypedExpression for PercentileCont<R, T, E> {
+    fn expression_as_sql(&self) -> &sql::ToSql {
+        self
+    }
+
+    fn upcast_expression(&self) -> expression::SharedExpression {
+        rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        self.within_group.get_by().accept_expression(visitor);
+        if let Some(ref filter) = self.filter {
+            filter.accept_predicate(visitor);
+        }
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_expression(self.within_group.get_by_mut(), rewriter);
+        if let Some(ref mut filter) = self.filter {
+            visitor::rewrite_shared_predicate(filter, rewriter);
+        }
+    }
+}
+
+impl<R: Clone + 'static, T: Clone + 'static, E: PercentileContArg<R, T> + 'static> expression::Expression<R> for PercentileCont<R, T, E> { }
+
+impl<R: Clone + 'static, T: Clone + 'static, E: PercentileContArg<R, T> + 'static> select_query::HasFilter for PercentileCont<R, T, E> {
+    fn get_filter(&self) -> &Option<predicate::SharedPredicate> { &self.filter }
+    fn set_filter(&mut self, predicate: predicate::SharedPredicate) { self.filter = Some(predicate); }
+    fn unset_filter(&mut self) { self.filter = None; }
+}
+
+impl PercentileContArg<f64, i8> for field::I8Field {}
+impl PercentileContArg<f64, i16> for field::I16Field {}
+impl PercentileContArg<f64, i32> for field::I32Field {}
+impl PercentileContArg<f64, i64> for field::I64Field {}
+impl PercentileContArg<f64, f32> for field::F32Field {}
+impl PercentileContArg<f64, f64> for field::F64Field {}
+
+/// `COUNT(*)`, with an optional FILTER (WHERE ...).
+#[derive(Clone)]
+pub struct CountAll {
+    pub filter: Option<predicate::SharedPredicate>
+}
+
+/// Unfiltered `COUNT(*)`, keeps `CountAll` usable as a value.
+#[allow(non_upper_case_globals)]
+pub const CountAll: CountAll = CountAll { filter: None };
 
 impl expression::UntypedExpression for CountAll {
     fn expression_as_sql(&self) -> &sql::ToSql {
@@ -103,6 +402,58 @@ impl expression::UntypedExpression for CountAll {
     fn upcast_expression(&self) -> expression::SharedExpression {
         rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
     }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        if let Some(ref filter) = self.filter {
+            filter.accept_predicate(visitor);
+        }
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        if let Some(ref mut filter) = self.filter {
+            visitor::rewrite_shared_predicate(filter, rewriter);
+        }
+    }
 }
 
 impl expression::Expression<i64> for CountAll { }
+
+impl select_query::HasFilter for CountAll {
+    fn get_filter(&self) -> &Option<predicate::SharedPredicate> { &self.filter }
+    fn set_filter(&mut self, predicate: predicate::SharedPredicate) { self.filter = Some(predicate); }
+    fn unset_filter(&mut self) { self.filter = None; }
+}
+
+/// `GROUPING(...)` bitmask telling subtotal rows of ROLLUP, CUBE and GROUPING SETS apart.
+#[derive(Clone)]
+pub struct Grouping {
+    pub expressions: Vec<expression::SharedExpression>
+}
+
+impl Grouping {
+    pub fn new(fields: &[&expression::UntypedExpression]) -> Grouping {
+        Grouping { expressions: fields.iter().map(|f| f.upcast_expression()).collect() }
+    }
+}
+
+impl expression::UntypedExpression for Grouping {
+    fn expression_as_sql(&self) -> &sql::ToSql {
+        self
+    }
+
+    fn upcast_expression(&self) -> expression::SharedExpression {
+        rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
+    }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        for expression in self.expressions.iter() {
+            expression.accept_expression(visitor);
+        }
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_expressions(&mut self.expressions, rewriter)
+    }
+}
+
+impl expression::Expression<i32> for Grouping { }
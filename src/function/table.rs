@@ -0,0 +1,141 @@
+use std::rc;
+use std::marker;
+use serialize::json;
+
+use expression;
+use field;
+use from;
+use select_query;
+use sql;
+use visitor::{self, Visitor, Rewriter};
+
+#[derive(Clone)]
+/// Set-returning function that can be used as a FROM source.
+pub struct TableFunction<T> {
+    name: String,
+    args: Vec<expression::SharedExpression>,
+    array_args: bool,
+    alias: Option<String>,
+    columns: Vec<String>,
+    /// Number of column aliases given when it differs from the number of columns.
+    mismatched_alias: Option<usize>,
+
+    _marker: marker::PhantomData<T>
+}
+
+impl<T> TableFunction<T> {
+    pub fn new(name: &str, args: Vec<expression::SharedExpression>, columns: &[&str]) -> TableFunction<T> {
+        TableFunction {
+            name: name.to_string(),
+            args: args,
+            array_args: false,
+            alias: None,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            mismatched_alias: None,
+
+            _marker: marker::PhantomData
+        }
+    }
+
+    pub fn get_name(&self) -> &String { &self.name }
+    pub fn get_args(&self) -> &Vec<expression::SharedExpression> { &self.args }
+    pub fn is_array_args(&self) -> bool { self.array_args }
+    pub fn get_alias(&self) -> &Option<String> { &self.alias }
+    pub fn get_columns(&self) -> &Vec<String> { &self.columns }
+    pub fn get_mismatched_alias(&self) -> Option<usize> { self.mismatched_alias }
+
+    fn column<R: Clone>(&self, idx: usize) -> field::NamedField<R> {
+        let qual = self.alias.as_ref().unwrap_or(&self.name);
+        // Functions built with fewer names than columns fall back to the function name
+        let name = self.columns.get(idx).unwrap_or(&self.name);
+        field::NamedField::new_qual(name, qual, qual)
+    }
+}
+
+impl<T: Clone> TableFunction<T> {
+    /// Names the function in FROM and optionally renames its output columns.
+    ///
+    /// A wrong number of column names keeps the default ones and fails rendering.
+    pub fn alias(&self, alias: &str, columns: &[&str]) -> TableFunction<T> {
+        with_clone!(self, func, {
+            func.alias = Some(alias.to_string());
+            func.mismatched_alias = None;
+            if columns.len() == func.columns.len() {
+                func.columns = columns.iter().map(|c| c.to_string()).collect();
+            } else if !columns.is_empty() {
+                func.mismatched_alias = Some(columns.len());
+            }
+        })
+    }
+}
+
+impl<T: Clone + 'static> TableFunction<T> {
+    pub fn lateral(&self) -> from::Lateral {
+        from::Lateral::new(self)
+    }
+}
+
+impl<T1: Clone> TableFunction<(T1,)> {
+    pub fn fields(&self) -> (field::NamedField<T1>,) {
+        (self.column(0),)
+    }
+}
+
+impl<T1: Clone, T2: Clone> TableFunction<(T1, T2)> {
+    pub fn fields(&self) -> (field::NamedField<T1>, field::NamedField<T2>) {
+        (self.column(0), self.column(1))
+    }
+}
+
+impl<T: Clone + 'static> from::From for TableFunction<T> {
+    fn as_sql(&self) -> &sql::FromToSql {
+        self
+    }
+
+    fn upcast_from(&self) -> from::SharedFrom {
+        rc::Rc::new(Box::new(self.clone()))
+    }
+
+    fn from_names(&self) -> Vec<String> {
+        vec![self.alias.as_ref().unwrap_or(&self.name).to_string()]
+    }
+
+    fn accept_from(&self, visitor: &mut Visitor) {
+        for arg in self.args.iter() {
+            arg.accept_expression(visitor);
+        }
+    }
+
+    fn rewrite_from(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_expressions(&mut self.args, rewriter)
+    }
+
+    fn column_names(&self) -> Option<Vec<String>> {
+        Some(self.columns.clone())
+    }
+}
+
+impl<T: Clone + 'static> select_query::Selectable<()> for TableFunction<T> {}
+
+pub fn generate_series<T: Clone>(start: &expression::Expression<T>, stop: &expression::Expression<T>) -> TableFunction<(T,)> {
+    TableFunction::new("generate_series", vec![start.upcast_expression(), stop.upcast_expression()], &["generate_series"])
+}
+
+pub fn generate_series_step<T: Clone, S: Clone>(start: &expression::Expression<T>, stop: &expression::Expression<T>, step: &expression::Expression<S>) -> TableFunction<(T,)> {
+    TableFunction::new("generate_series", vec![start.upcast_expression(), stop.upcast_expression(), step.upcast_expression()], &["generate_series"])
+}
+
+/// Expands a list of values (rendered as `ARRAY[...]`) to a set of rows.
+pub fn unnest<T: Clone>(values: &expression::ListExpression<T>) -> TableFunction<(T,)> {
+    let mut func = TableFunction::new("unnest", vec![values.upcast_expression()], &["unnest"]);
+    func.array_args = true;
+    func
+}
+
+pub fn jsonb_array_elements(json: &expression::Expression<json::Json>) -> TableFunction<(json::Json,)> {
+    TableFunction::new("jsonb_array_elements", vec![json.upcast_expression()], &["value"])
+}
+
+pub fn jsonb_each(json: &expression::Expression<json::Json>) -> TableFunction<(String, json::Json)> {
+    TableFunction::new("jsonb_each", vec![json.upcast_expression()], &["key", "value"])
+}
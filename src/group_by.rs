@@ -1,16 +1,55 @@
 use super::expression;
 
+#[derive(Clone)]
+pub enum GroupingElement {
+    Rollup(Vec<expression::SharedExpression>),
+    Cube(Vec<expression::SharedExpression>),
+    GroupingSets(Vec<Vec<expression::SharedExpression>>)
+}
+
 #[derive(Clone)]
 pub struct GroupBy {
-    by: Vec<expression::SharedExpression>
+    by: Vec<expression::SharedExpression>,
+    grouping: Vec<GroupingElement>
 }
 
 impl GroupBy {
     pub fn get_by(&self) -> &Vec<expression::SharedExpression> { &self.by }
+    pub fn get_grouping(&self) -> &Vec<GroupingElement> { &self.grouping }
+    pub fn get_by_mut(&mut self) -> &mut Vec<expression::SharedExpression> { &mut self.by }
+    pub fn get_grouping_mut(&mut self) -> &mut Vec<GroupingElement> { &mut self.grouping }
+}
+
+fn upcast_all(fields: &[&expression::UntypedExpression]) -> Vec<expression::SharedExpression> {
+    fields.iter().map(|f| f.upcast_expression()).collect()
 }
 
 impl GroupBy {
+    pub fn new() -> GroupBy {
+        GroupBy { by: vec![], grouping: vec![] }
+    }
+
     pub fn by(fields: &[&expression::UntypedExpression]) -> GroupBy {
-        GroupBy { by: fields.iter().map(|f| f.upcast_expression()).collect() }
+        GroupBy { by: upcast_all(fields), grouping: vec![] }
+    }
+
+    /// Replaces plain expressions, keeping ROLLUP, CUBE and GROUPING SETS elements.
+    pub fn replace_by(&self, fields: &[&expression::UntypedExpression]) -> GroupBy {
+        with_clone!(self, group_by, group_by.by = upcast_all(fields))
+    }
+
+    pub fn rollup(&self, fields: &[&expression::UntypedExpression]) -> GroupBy {
+        with_clone!(self, group_by, group_by.grouping.push(GroupingElement::Rollup(upcast_all(fields))))
+    }
+
+    pub fn cube(&self, fields: &[&expression::UntypedExpression]) -> GroupBy {
+        with_clone!(self, group_by, group_by.grouping.push(GroupingElement::Cube(upcast_all(fields))))
+    }
+
+    /// Every set is a list of fields, an empty one stands for the grand total `()`.
+    pub fn grouping_sets(&self, sets: &[&[&expression::UntypedExpression]]) -> GroupBy {
+        with_clone!(self, group_by, group_by.grouping.push(
+            GroupingElement::GroupingSets(sets.iter().map(|set| upcast_all(*set)).collect())
+        ))
     }
 }
@@ -0,0 +1,98 @@
+use super::from;
+use super::field;
+use super::expression;
+use super::predicate;
+use super::select_query;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IndexMethod {
+    BTree,
+    Hash,
+    Gist,
+    SpGist,
+    Gin,
+    Brin
+}
+
+pub trait Indexable: from::Table + Sized {
+    fn index(&self, name: &str) -> CreateIndexQuery {
+        CreateIndexQuery::new(self, name)
+    }
+}
+
+#[derive(Clone)]
+pub struct CreateIndexQuery {
+    name: String,
+    table: from::SharedTable,
+    on: Vec<expression::SharedExpression>,
+    unique: bool,
+    concurrently: bool,
+    if_not_exists: bool,
+    method: Option<IndexMethod>,
+    include: Vec<field::SharedField>,
+    where_: Option<predicate::SharedPredicate>
+}
+
+impl CreateIndexQuery {
+    pub fn get_name(&self) -> &String { &self.name }
+    pub fn get_table(&self) -> &from::SharedTable { &self.table }
+    pub fn get_on(&self) -> &Vec<expression::SharedExpression> { &self.on }
+    pub fn is_unique(&self) -> bool { self.unique }
+    pub fn is_concurrently(&self) -> bool { self.concurrently }
+    pub fn is_if_not_exists(&self) -> bool { self.if_not_exists }
+    pub fn get_method(&self) -> &Option<IndexMethod> { &self.method }
+    pub fn get_include(&self) -> &Vec<field::SharedField> { &self.include }
+    pub fn get_where(&self) -> &Option<predicate::SharedPredicate> { &self.where_ }
+
+    pub fn new(table: &from::Table, name: &str) -> CreateIndexQuery {
+        CreateIndexQuery {
+            name: name.to_string(),
+            table: table.upcast_table(),
+            on: vec![],
+            unique: false,
+            concurrently: false,
+            if_not_exists: false,
+            method: None,
+            include: vec![],
+            where_: None
+        }
+    }
+
+    /// Indexed fields or arbitrary expressions (e.g. `RawExpression::new("lower(name)")`).
+    pub fn on(mut self, exprs: &[&expression::UntypedExpression]) -> CreateIndexQuery {
+        self.on.extend(exprs.iter().map(|e| e.upcast_expression()));
+        self
+    }
+
+    pub fn unique(mut self) -> CreateIndexQuery {
+        self.unique = true;
+        self
+    }
+
+    pub fn concurrently(mut self) -> CreateIndexQuery {
+        self.concurrently = true;
+        self
+    }
+
+    pub fn if_not_exists(mut self) -> CreateIndexQuery {
+        self.if_not_exists = true;
+        self
+    }
+
+    pub fn using(mut self, method: IndexMethod) -> CreateIndexQuery {
+        self.method = Some(method);
+        self
+    }
+
+    pub fn include(mut self, fields: &[&field::Field]) -> CreateIndexQuery {
+        self.include.extend(fields.iter().map(|f| f.upcast_field()));
+        self
+    }
+}
+
+// Partial index predicate
+impl select_query::Queryable for CreateIndexQuery {
+    fn get_where(&self) -> &Option<predicate::SharedPredicate> { &self.where_ }
+    fn set_where(&mut self, predicate: predicate::SharedPredicate) { self.where_ = Some(predicate); }
+    fn unset_where(&mut self) { self.where_ = None; }
+}
@@ -28,6 +28,69 @@ impl<T> InsertValue<T> {
     }
 }
 
+/// Value of a typed VALUES row with its type erased.
+pub trait UntypedInsertValue {
+    /// `None` for DEFAULT.
+    fn get_expression(&self) -> Option<&expression::SharedExpression>;
+    fn set_expression(&mut self, expression: expression::SharedExpression);
+}
+
+impl<T> UntypedInsertValue for InsertValue<T> {
+    fn get_expression(&self) -> Option<&expression::SharedExpression> {
+        match self {
+            &InsertValue::Value{ref expression, ..} => Some(expression),
+            &InsertValue::Default => None
+        }
+    }
+
+    fn set_expression(&mut self, expression: expression::SharedExpression) {
+        *self = InsertValue::Value {
+            expression: expression,
+            _marker: marker::PhantomData
+        }
+    }
+}
+
+/// Row of typed VALUES, values are in the order of the inserted columns.
+pub trait InsertRow {
+    fn get_row_values(&self) -> Vec<&UntypedInsertValue>;
+    fn get_row_values_mut(&mut self) -> Vec<&mut UntypedInsertValue>;
+}
+
+impl InsertRow for () {
+    fn get_row_values(&self) -> Vec<&UntypedInsertValue> { vec![] }
+    fn get_row_values_mut(&mut self) -> Vec<&mut UntypedInsertValue> { vec![] }
+}
+
+macro_rules! insert_row_tuple {
+    ($($t:ident, $var:ident),+) => (
+        impl<$($t,)+> InsertRow for ($(InsertValue<$t>),+,) {
+            fn get_row_values(&self) -> Vec<&UntypedInsertValue> {
+                let &($(ref $var,)+) = self;
+                vec![$($var as &UntypedInsertValue),+]
+            }
+
+            fn get_row_values_mut(&mut self) -> Vec<&mut UntypedInsertValue> {
+                let &mut ($(ref mut $var,)+) = self;
+                vec![$($var as &mut UntypedInsertValue),+]
+            }
+        }
+    )
+}
+
+insert_row_tuple!(T1, t1);
+insert_row_tuple!(T1, t1, T2, t2);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11);
+insert_row_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11, T12, t12);
+
 impl<'a, 'b, T> ToInsertValue<T> for &'a (expression::Expression<T> + 'b) {
     fn to_insert_val(&self) -> InsertValue<T> {
         InsertValue::new(*self)
@@ -136,6 +199,10 @@ impl<T: Clone, V: Clone, M: Clone, RT: Clone, RL: Clone> InsertQuery<T, V, M, RT
     pub fn get_values(&self) -> &Insert<T, V, M> { &self.values }
     pub fn get_returning(&self) -> &Option<select_query::Select> { &self.returning }
 
+    pub fn get_cols_mut(&mut self) -> &mut Option<Vec<field::SharedField>> { &mut self.cols }
+    pub fn get_values_mut(&mut self) -> &mut Insert<T, V, M> { &mut self.values }
+    pub fn get_returning_mut(&mut self) -> &mut Option<select_query::Select> { &mut self.returning }
+
     pub fn push(&mut self, value: V) {
 
         let mut reassign = false;
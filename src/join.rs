@@ -1,6 +1,40 @@
+use std::fmt;
+use std::error;
+
 use super::from;
+use super::field;
 use super::predicate;
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsingError {
+    /// Field is not a column of both sides of the join.
+    UnknownField(String),
+    /// Field is listed in USING more than once.
+    DuplicateField(String),
+    /// USING has no fields.
+    NoFields
+}
+
+impl fmt::Display for UsingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &UsingError::UnknownField(ref name) => write!(f, "Field {} is not a column of both sides of the join", name),
+            &UsingError::DuplicateField(ref name) => write!(f, "Field {} is listed in USING more than once", name),
+            &UsingError::NoFields => write!(f, "JOIN ... USING requires at least one field")
+        }
+    }
+}
+
+impl error::Error for UsingError {
+    fn description(&self) -> &str {
+        match self {
+            &UsingError::UnknownField(_) => "Field is not a column of both sides of the join",
+            &UsingError::DuplicateField(_) => "Field is listed in USING more than once",
+            &UsingError::NoFields => "JOIN ... USING requires at least one field"
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub enum ConditionedJoinType {
@@ -35,6 +69,12 @@ pub enum Join {
     UnconditionedJoin {
         join_type: UnconditionedJoinType,
         from: from::SharedFrom
+    },
+
+    UsingJoin {
+        join_type: ConditionedJoinType,
+        from: from::SharedFrom,
+        using: Vec<field::SharedField>
     }
 }
 
@@ -87,4 +127,24 @@ impl Join {
         Join::UnconditionedJoin{ join_type: UnconditionedJoinType::CrossJoin, from: from }
     }
 
+    pub fn using(join_type: ConditionedJoinType, from: from::SharedFrom, using: Vec<field::SharedField>) -> Join {
+        Join::UsingJoin{ join_type: join_type, from: from, using: using }
+    }
+
+    pub fn get_from(&self) -> &from::SharedFrom {
+        match self {
+            &Join::ConditionedJoin{ref from, ..} => from,
+            &Join::UnconditionedJoin{ref from, ..} => from,
+            &Join::UsingJoin{ref from, ..} => from
+        }
+    }
+
+    pub fn get_from_mut(&mut self) -> &mut from::SharedFrom {
+        match self {
+            &mut Join::ConditionedJoin{ref mut from, ..} => from,
+            &mut Join::UnconditionedJoin{ref mut from, ..} => from,
+            &mut Join::UsingJoin{ref mut from, ..} => from
+        }
+    }
+
 }
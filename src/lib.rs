@@ -3,10 +3,13 @@ extern crate time;
 
 #[cfg(feature = "postgres")]
 extern crate postgres;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
 extern crate uuid;
 
 mod prelude {
     pub use {
+        ToSharedPredicate,
         ToIsPredicate,
         ToOrPredicate,
         ToAndPredicate,
@@ -24,9 +27,21 @@ mod prelude {
         ToFieldUpdate,
         ToInsertValue,
         Deletable,
+        Truncatable,
+        Alterable,
+        Indexable,
         ToExpression,
-        ToListExpression
+        ToListExpression,
+        ToAliasedExpression,
+        HasHaving,
+        HasFilter,
+        Executable,
+        Visitable
     };
+
+    #[cfg(feature = "postgres")]
+    #[allow(deprecated)]
+    pub use PostgresQuery;
 }
 
 pub use field::{
@@ -60,17 +75,23 @@ pub use predicate::{
     ExcludePredicate, ToExcludePredicate,
     LikePredicate, ToLikePredicate,
     IsNullPredicate, ToIsNullPredicate,
-    RawPredicate
+    RawPredicate,
+    ToSharedPredicate
 };
 
 pub use select_query::{
     Selectable,
     Queryable,
+    HasHaving,
+    HasFilter,
     Orderable,
     SelectQuery,
     SharedSelectQuery,
     ToSelectQuery,
     Select,
+    SelectFor,
+    LockStrength,
+    LockWait,
     NoResult,
     LimitOne,
     LimitTwo,
@@ -90,6 +111,8 @@ pub use insert_query::{
     Insertable,
     ToInsertValue,
     InsertValue,
+    UntypedInsertValue,
+    InsertRow,
 };
 
 pub use delete_query::{
@@ -97,6 +120,97 @@ pub use delete_query::{
     Deletable,
 };
 
+pub use truncate_query::{
+    TruncateQuery,
+    Truncatable,
+    TruncateIdentity,
+    TruncateBehavior,
+};
+
+pub use schema::{
+    ColumnType,
+    ToColumnType,
+    ColumnDef,
+    TableSchema,
+    CreateTableQuery,
+};
+
+pub use alter_table_query::{
+    AlterTableQuery,
+    AlterAction,
+    Alterable,
+    Constraint,
+};
+
+pub use index_query::{
+    CreateIndexQuery,
+    IndexMethod,
+    Indexable,
+};
+
+pub use migration::{
+    Migration,
+    MigrationStep,
+    MigrationError,
+    AppliedMigration,
+    Migrator,
+};
+
+pub use exec::{
+    Cardinality,
+    ExecError,
+    Executor,
+    Executable,
+    execute_statements,
+};
+
+pub use transaction::{
+    TransactionOptions,
+    IsolationLevel,
+};
+
+pub use exec::transaction::{
+    Transaction,
+    transaction,
+    retry_transaction,
+};
+
+pub use exec::cache::{
+    StatementCache,
+};
+
+pub use exec::template::{
+    PreparedTemplate,
+};
+
+#[cfg(feature = "postgres")]
+pub use exec::postgres::{
+    CachedConnection,
+};
+
+#[cfg(feature = "postgres")]
+#[allow(deprecated)]
+pub use exec::postgres::{
+    FromPostgresRow,
+    PostgresQuery,
+};
+
+pub use exec::mock::{
+    MockExecutor,
+    Statement,
+};
+
+pub use exec::row::{
+    Value,
+    Row,
+    RowError,
+    MemoryRow,
+    FromValue,
+    ToValue,
+    ValueType,
+    FromRow,
+};
+
 pub use expression::{
     BoxedExpression,
     UntypedExpression,
@@ -108,9 +222,15 @@ pub use expression::{
     ToListExpression
 };
 
-pub use sql::{SqlContext, ToSql, QueryToSql, FromToSql, ToPredicateValue};
+pub use alias::{
+    AliasedExpression,
+    ToAliasedExpression
+};
+
+pub use sql::{SqlContext, SqlError, ToSql, QueryToSql, StatementsToSql, FromToSql, ToPredicateValue, ToSqlLiteral};
 #[cfg(feature = "postgres")] pub use sql::{AsPostgresValue};
-pub use from::{TableDef, Table, BoxedTable, SharedTable, From, BoxedFrom, SharedFrom};
+pub use from::{TableDef, Table, BoxedTable, SharedTable, From, BoxedFrom, SharedFrom, FromSelect, Lateral};
+pub use join::{UsingError};
 
 pub use function::{
     Sum, SumArg,
@@ -118,13 +238,44 @@ pub use function::{
     Max, MaxArg,
     Avg, AvgArg,
     Count, CountArg,
-    CountAll
+    BoolAnd, BoolAndArg,
+    BoolOr, BoolOrArg,
+    ArrayAgg, ArrayAggArg,
+    JsonAgg, JsonAggArg,
+    Stddev, StddevArg,
+    Variance, VarianceArg,
+    StringAgg, StringAggArg,
+    PercentileCont, PercentileContArg,
+    JsonObjectAgg, json_object_agg,
+    CountAll,
+    AggregateModifiers,
+    Grouping,
+    TableFunction,
+    generate_series,
+    generate_series_step,
+    unnest,
+    jsonb_array_elements,
+    jsonb_each
 };
 
 pub use placeholder::{
     Placeholder
 };
 
+pub use visitor::{
+    Visitor,
+    Visitable,
+    PredicateKind,
+    Rewriter,
+    Rewritable,
+    RewritableSelect,
+};
+
+pub use tenant::{
+    TenantScope,
+    TenantError,
+};
+
 macro_rules! with_clone{
     ($slf: ident, $v:ident, $ex:expr) => ({
         let mut $v = $slf.clone();
@@ -133,6 +284,8 @@ macro_rules! with_clone{
     })
 }
 
+#[macro_use]
+mod table_macro;
 mod field;
 mod predicate;
 mod select_query;
@@ -141,8 +294,18 @@ mod insert_query;
 #[macro_use]
 mod delete_query;
 mod update_query;
+mod truncate_query;
+mod schema;
+mod alter_table_query;
+mod index_query;
+mod migration;
+mod transaction;
 pub mod sql;
+mod value;
+#[macro_use]
+pub mod exec;
 mod expression;
+mod alias;
 mod order_by;
 mod from;
 mod join;
@@ -150,3 +313,5 @@ mod distinct;
 mod group_by;
 mod function;
 mod placeholder;
+mod visitor;
+mod tenant;
@@ -0,0 +1,460 @@
+use std::rc;
+use std::fmt;
+use std::error;
+
+use super::from::{self, Table};
+use super::field;
+use super::schema;
+use super::expression::{self, ToExpression};
+use super::insert_query::{Insertable};
+use super::delete_query::{Deletable};
+use super::select_query::{Queryable};
+use super::predicate::{ToIsPredicate};
+use super::sql::{self, QueryToSql};
+use super::transaction;
+use super::exec::{self, Executor};
+use super::exec::row;
+
+pub type SharedQuery = rc::Rc<Box<sql::QueryToSql + 'static>>;
+
+#[derive(Clone)]
+pub enum MigrationStep {
+    Query(SharedQuery),
+    Raw(String)
+}
+
+impl MigrationStep {
+    pub fn to_final_statements(&self, ctx: &mut sql::SqlContext) -> Result<Vec<String>, sql::SqlError> {
+        match self {
+            &MigrationStep::Query(ref query) => inline_to_final_statements(&***query, ctx),
+            &MigrationStep::Raw(ref sql) => Ok(vec![sql.to_string()])
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Migration {
+    version: i64,
+    name: String,
+    up: Vec<MigrationStep>,
+    down: Vec<MigrationStep>,
+    transactional: bool
+}
+
+impl Migration {
+    pub fn get_version(&self) -> i64 { self.version }
+    pub fn get_name(&self) -> &String { &self.name }
+    pub fn get_up(&self) -> &Vec<MigrationStep> { &self.up }
+    pub fn get_down(&self) -> &Vec<MigrationStep> { &self.down }
+    pub fn is_transactional(&self) -> bool { self.transactional }
+
+    pub fn new(version: i64, name: &str) -> Migration {
+        Migration {
+            version: version,
+            name: name.to_string(),
+            up: vec![],
+            down: vec![],
+            transactional: true
+        }
+    }
+
+    /// Runs the migration outside of a transaction, for statements like `CREATE INDEX CONCURRENTLY`.
+    pub fn without_transaction(mut self) -> Migration {
+        self.transactional = false;
+        self
+    }
+
+    pub fn up<Q: sql::QueryToSql + 'static>(mut self, query: Q) -> Migration {
+        self.up.push(MigrationStep::Query(rc::Rc::new(Box::new(query))));
+        self
+    }
+
+    pub fn up_sql(mut self, sql: &str) -> Migration {
+        self.up.push(MigrationStep::Raw(sql.to_string()));
+        self
+    }
+
+    pub fn down<Q: sql::QueryToSql + 'static>(mut self, query: Q) -> Migration {
+        self.down.push(MigrationStep::Query(rc::Rc::new(Box::new(query))));
+        self
+    }
+
+    pub fn down_sql(mut self, sql: &str) -> Migration {
+        self.down.push(MigrationStep::Raw(sql.to_string()));
+        self
+    }
+
+    pub fn is_reversible(&self) -> bool {
+        !self.down.is_empty()
+    }
+
+    pub fn up_to_sql(&self, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        steps_to_sql(&self.up, ctx)
+    }
+
+    pub fn down_to_sql(&self, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        steps_to_sql(&self.down, ctx)
+    }
+
+    /// Checksum of the up steps, stored with the applied migration to detect migrations edited
+    /// after they were applied.
+    ///
+    /// Raw SQL is hashed as written and queries are always rendered for PostgreSQL, so the checksum
+    /// doesn't depend on the database the migration is applied to.
+    pub fn checksum(&self) -> String {
+        let mut ctx = sql::SqlContext::new(Box::new(sql::PostgreSqlAdapter));
+        let mut input = vec![CHECKSUM_FORMAT.to_string()];
+        for step in self.up.iter() {
+            match step {
+                &MigrationStep::Query(ref query) => {
+                    ctx.reset();
+                    ctx.set_inline_values(true);
+                    input.push(query.to_final_sql(&mut ctx));
+                },
+                &MigrationStep::Raw(ref sql) => input.push(sql.to_string())
+            }
+        }
+        checksum(&input.connect("\n"))
+    }
+}
+
+/// Version of the checksummed input, changed when the rendering of query steps changes.
+const CHECKSUM_FORMAT: &'static str = "deuterium-migration-v1";
+
+// Migrations are applied as plain statements, so values are inlined. Every statement is independent,
+// so nothing rendered before is kept in the shared context.
+fn inline_to_final_statements(query: &sql::QueryToSql, ctx: &mut sql::SqlContext) -> Result<Vec<String>, sql::SqlError> {
+    ctx.reset();
+    let inline_values = ctx.is_inline_values();
+    ctx.set_inline_values(true);
+    let sql = query.try_to_final_statements(ctx);
+    ctx.set_inline_values(inline_values);
+    sql
+}
+
+fn steps_to_sql(steps: &Vec<MigrationStep>, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+    let mut sql = vec![];
+    for step in steps.iter() {
+        sql.extend(try!(step.to_final_statements(ctx)).into_iter());
+    }
+    Ok(sql)
+}
+
+// 64-bit FNV-1a, stable across Rust versions unlike the std hashers
+fn checksum(sql: &str) -> String {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in sql.as_bytes().iter() {
+        hash = hash ^ (*byte as u64);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Sql(sql::SqlError),
+    /// Applied migration was changed since it was applied.
+    ChecksumMismatch(i64),
+    /// Applied migration is not defined in the migrator.
+    UnknownVersion(i64),
+    /// Migration has no down steps.
+    Irreversible(i64),
+    /// Several migrations are defined with the same version.
+    DuplicateVersion(i64),
+    Exec(exec::ExecError)
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &MigrationError::Sql(ref err) => write!(f, "{}", err),
+            &MigrationError::ChecksumMismatch(version) => write!(f, "Migration {} was changed after it was applied", version),
+            &MigrationError::UnknownVersion(version) => write!(f, "Migration {} is applied but not defined", version),
+            &MigrationError::Irreversible(version) => write!(f, "Migration {} can't be reverted", version),
+            &MigrationError::DuplicateVersion(version) => write!(f, "Migration {} is defined more than once", version),
+            &MigrationError::Exec(ref err) => write!(f, "{}", err)
+        }
+    }
+}
+
+impl error::Error for MigrationError {
+    fn description(&self) -> &str {
+        match self {
+            &MigrationError::Sql(ref err) => error::Error::description(err),
+            &MigrationError::ChecksumMismatch(_) => "Migration was changed after it was applied",
+            &MigrationError::UnknownVersion(_) => "Migration is applied but not defined",
+            &MigrationError::Irreversible(_) => "Migration can't be reverted",
+            &MigrationError::DuplicateVersion(_) => "Migration is defined more than once",
+            &MigrationError::Exec(ref err) => error::Error::description(err)
+        }
+    }
+}
+
+impl From<sql::SqlError> for MigrationError {
+    fn from(err: sql::SqlError) -> MigrationError {
+        MigrationError::Sql(err)
+    }
+}
+
+impl From<exec::ExecError> for MigrationError {
+    fn from(err: exec::ExecError) -> MigrationError {
+        MigrationError::Exec(err)
+    }
+}
+
+#[derive(Clone)]
+pub struct Migrator {
+    table: from::TableDef,
+    migrations: Vec<Migration>,
+    duplicates: Vec<i64>,
+    dry_run: bool
+}
+
+impl Migrator {
+    pub fn get_table(&self) -> &from::TableDef { &self.table }
+    pub fn get_migrations(&self) -> &Vec<Migration> { &self.migrations }
+
+    pub fn new() -> Migrator {
+        Migrator {
+            table: from::TableDef::new("schema_migrations"),
+            migrations: vec![],
+            duplicates: vec![],
+            dry_run: false
+        }
+    }
+
+    pub fn table(mut self, name: &str) -> Migrator {
+        self.table = from::TableDef::new(name);
+        self
+    }
+
+    /// `up` and `down` print the statements of migrations instead of running them. The migrations
+    /// table is still created and read to know which migrations are pending.
+    pub fn dry_run(mut self) -> Migrator {
+        self.dry_run = true;
+        self
+    }
+
+    /// Adds a migration, a duplicate version is reported by `verify` and everything that runs it.
+    pub fn migration(mut self, migration: Migration) -> Migrator {
+        if self.migrations.iter().any(|m| m.version == migration.version) {
+            self.duplicates.push(migration.version);
+            return self
+        }
+
+        let pos = self.migrations.iter().position(|m| m.version > migration.version).unwrap_or(self.migrations.len());
+        self.migrations.insert(pos, migration);
+        self
+    }
+
+    fn version_field(&self) -> field::I64Field { field::NamedField::new("version", self.table.get_table_name()) }
+    fn name_field(&self) -> field::StringField { field::NamedField::new("name", self.table.get_table_name()) }
+    fn checksum_field(&self) -> field::StringField { field::NamedField::new("checksum", self.table.get_table_name()) }
+
+    pub fn create_table(&self) -> schema::CreateTableQuery {
+        let applied_at = field::TimespecField::new("applied_at", self.table.get_table_name());
+        self.table.schema()
+            .column(schema::ColumnDef::of(&self.version_field()).primary_key())
+            .column(schema::ColumnDef::of(&self.name_field()))
+            .column(schema::ColumnDef::of(&self.checksum_field()))
+            .column(schema::ColumnDef::of(&applied_at).default(&expression::RawExpression::new("CURRENT_TIMESTAMP")))
+            .create()
+            .if_not_exists()
+    }
+
+    /// Checks that versions are unique and every applied migration is still defined and unchanged.
+    pub fn verify(&self, applied: &[AppliedMigration]) -> Result<(), MigrationError> {
+        if let Some(version) = self.duplicates.first() {
+            return Err(MigrationError::DuplicateVersion(*version))
+        }
+
+        for applied in applied.iter() {
+            match self.migrations.iter().find(|m| m.version == applied.version) {
+                Some(migration) => {
+                    if migration.checksum() != applied.checksum {
+                        return Err(MigrationError::ChecksumMismatch(applied.version))
+                    }
+                },
+                None => return Err(MigrationError::UnknownVersion(applied.version))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn pending(&self, applied: &[AppliedMigration]) -> Vec<&Migration> {
+        self.migrations.iter().filter(|m| !applied.iter().any(|a| a.version == m.version)).collect()
+    }
+
+    /// Migrations to revert, latest first.
+    pub fn revertible(&self, applied: &[AppliedMigration], steps: usize) -> Vec<&Migration> {
+        self.migrations.iter().rev().filter(|m| applied.iter().any(|a| a.version == m.version)).take(steps).collect()
+    }
+
+    fn up_statements(&self, migration: &Migration, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        let mut sql = try!(migration.up_to_sql(ctx));
+
+        let mut record = self.table.insert_fields(&[&self.version_field(), &self.name_field(), &self.checksum_field()]);
+        record.push_untyped(&[
+            migration.version.as_expr(),
+            migration.name.as_expr(),
+            migration.checksum().as_expr()
+        ]);
+
+        sql.extend(try!(inline_to_final_statements(&record, ctx)).into_iter());
+        Ok(sql)
+    }
+
+    fn down_statements(&self, migration: &Migration, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        if !migration.is_reversible() {
+            return Err(MigrationError::Irreversible(migration.version))
+        }
+
+        let mut sql = try!(migration.down_to_sql(ctx));
+
+        let record = self.table.delete().where_(self.version_field().is(migration.version));
+
+        sql.extend(try!(inline_to_final_statements(&record, ctx)).into_iter());
+        Ok(sql)
+    }
+
+    /// SQL that `up` would run for already `applied` migrations (dry run).
+    pub fn plan_up(&self, applied: &[AppliedMigration], ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        try!(self.verify(applied));
+
+        let mut sql = vec![try!(self.create_table().try_to_final_sql(ctx))];
+        for migration in self.pending(applied).into_iter() {
+            let statements = try!(self.up_statements(migration, ctx));
+            sql.extend(in_transaction(migration, statements).into_iter());
+        }
+        Ok(sql)
+    }
+
+    /// SQL that `down` would run to revert `steps` latest migrations (dry run).
+    pub fn plan_down(&self, applied: &[AppliedMigration], steps: usize, ctx: &mut sql::SqlContext) -> Result<Vec<String>, MigrationError> {
+        try!(self.verify(applied));
+
+        let mut sql = vec![];
+        for migration in self.revertible(applied, steps).into_iter() {
+            let statements = try!(self.down_statements(migration, ctx));
+            sql.extend(in_transaction(migration, statements).into_iter());
+        }
+        Ok(sql)
+    }
+}
+
+fn in_transaction(migration: &Migration, statements: Vec<String>) -> Vec<String> {
+    if !migration.is_transactional() {
+        return statements
+    }
+
+    let mut sql = vec!["BEGIN;".to_string()];
+    sql.extend(statements.into_iter());
+    sql.push("COMMIT;".to_string());
+    sql
+}
+
+impl Migrator {
+    /// Creates the migrations table if needed and reads the applied migrations.
+    pub fn applied(&self, executor: &exec::Executor) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let mut ctx = executor.context();
+        let create_table = try!(self.create_table().try_to_final_sql(&mut ctx));
+        try!(executor.execute(&create_table, &ctx));
+
+        let sql = format!("SELECT version, name, checksum FROM {} ORDER BY version;", self.table.get_table_name());
+        let mut applied = vec![];
+        try!(executor.query_rows(&sql, &ctx, &mut |row| {
+            applied.push(AppliedMigration {
+                version: try!(row::get(row, 0)),
+                name: try!(row::get(row, 1)),
+                checksum: try!(row::get(row, 2))
+            });
+            Ok(())
+        }));
+        Ok(applied)
+    }
+
+    /// Applies pending migrations, each in its own transaction unless opted out. Returns applied versions.
+    pub fn up(&self, executor: &exec::Executor) -> Result<Vec<i64>, MigrationError> {
+        self.locked(executor, || {
+            let applied = try!(self.applied(executor));
+            try!(self.verify(&applied));
+
+            let mut versions = vec![];
+            for migration in self.pending(&applied).into_iter() {
+                let statements = try!(self.up_statements(migration, &mut executor.context()));
+                try!(self.run(executor, migration, statements));
+                versions.push(migration.version);
+            }
+            Ok(versions)
+        })
+    }
+
+    /// Reverts `steps` latest migrations, each in its own transaction unless opted out. Returns reverted versions.
+    pub fn down(&self, executor: &exec::Executor, steps: usize) -> Result<Vec<i64>, MigrationError> {
+        self.locked(executor, || {
+            let applied = try!(self.applied(executor));
+            try!(self.verify(&applied));
+
+            let mut versions = vec![];
+            for migration in self.revertible(&applied, steps).into_iter() {
+                let statements = try!(self.down_statements(migration, &mut executor.context()));
+                try!(self.run(executor, migration, statements));
+                versions.push(migration.version);
+            }
+            Ok(versions)
+        })
+    }
+
+    fn run(&self, executor: &exec::Executor, migration: &Migration, statements: Vec<String>) -> Result<(), MigrationError> {
+        if self.dry_run {
+            for sql in in_transaction(migration, statements).iter() {
+                println!("{}", sql);
+            }
+            return Ok(())
+        }
+
+        let ctx = executor.context();
+        if !migration.is_transactional() {
+            for sql in statements.iter() {
+                try!(executor.execute(sql, &ctx));
+            }
+            return Ok(())
+        }
+
+        try!(exec::transaction::transaction(executor, &transaction::TransactionOptions::new(), |trans| {
+            for sql in statements.iter() {
+                try!(trans.execute(sql, &ctx));
+            }
+            Ok(())
+        }));
+        Ok(())
+    }
+
+    // Runners started at the same time would apply the same migrations. Without a lock in the database
+    // (SQLite) the second one fails on the primary key of the migrations table instead.
+    fn locked<T, F>(&self, executor: &exec::Executor, f: F) -> Result<T, MigrationError>
+        where F: FnOnce() -> Result<T, MigrationError> {
+
+        let ctx = executor.context();
+        let (lock, unlock) = match ctx.adapter().advisory_lock(self.table.get_table_name()) {
+            Some(statements) => statements,
+            None => return f()
+        };
+
+        try!(executor.execute(&lock, &ctx));
+        let result = f();
+        let unlocked = executor.execute(&unlock, &ctx);
+
+        // Error of the run is more useful than a failed unlock
+        let value = try!(result);
+        try!(unlocked);
+        Ok(value)
+    }
+}
@@ -31,6 +31,10 @@ impl OrderBy {
         &self.by
     }
 
+    pub fn get_by_mut(&mut self) -> &mut expression::SharedExpression {
+        &mut self.by
+    }
+
     pub fn get_order(&self) -> &Order {
         &self.order
     }
@@ -3,21 +3,33 @@ use time;
 use serialize::json;
 
 use super::expression;
+use super::value::{ValueType};
 use super::sql;
+use super::visitor::{Visitor, Rewriter};
 
 #[derive(Clone, Copy)]
 pub struct Placeholder {
-    idx: u8
+    idx: u8,
+    value_type: Option<&'static str>
 }
 
 impl Placeholder {
     pub fn new(idx: u8) -> Placeholder {
-        Placeholder { idx: idx }
+        Placeholder { idx: idx, value_type: None }
+    }
+
+    /// Placeholder accepting only values of type `T` when bound to a `PreparedTemplate`.
+    pub fn typed<T: ValueType>(idx: u8) -> Placeholder {
+        Placeholder { idx: idx, value_type: Some(T::value_type()) }
     }
 
     pub fn get_idx(&self) -> u8 {
         self.idx
     }
+
+    pub fn get_value_type(&self) -> Option<&'static str> {
+        self.value_type
+    }
 }
 
 impl expression::UntypedExpression for Placeholder {
@@ -28,6 +40,12 @@ impl expression::UntypedExpression for Placeholder {
     fn upcast_expression(&self) -> expression::SharedExpression {
         rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
     }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        visitor.visit_placeholder(self)
+    }
+
+    fn rewrite_expression(&mut self, _rewriter: &mut Rewriter) {}
 }
 
 impl expression::ToExpression<bool> for Placeholder {}
@@ -1,3 +1,4 @@
+use super::super::visitor::{self, Visitor, Rewriter, PredicateKind};
 use super::{ToSharedPredicate};
 
 #[derive(Clone)]
@@ -16,4 +17,20 @@ impl ToAndPredicate for super::SharedPredicate {
     }
 }
 
-impl super::Predicate for AndPredicate { }
+impl super::Predicate for AndPredicate {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::And);
+        self.left.accept_predicate(visitor);
+        self.right.accept_predicate(visitor);
+        visitor.leave_predicate(PredicateKind::And);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_predicate(&mut self.left, rewriter);
+        visitor::rewrite_shared_predicate(&mut self.right, rewriter);
+    }
+}
@@ -1,3 +1,4 @@
+use super::super::visitor::{self, Visitor, Rewriter, PredicateKind};
 use super::{ToSharedPredicate};
 
 #[derive(Clone)]
@@ -9,7 +10,21 @@ pub trait ToExcludePredicate {
     fn exclude(&self) -> super::SharedPredicate;
 }
 
-impl super::Predicate for ExcludePredicate {}
+impl super::Predicate for ExcludePredicate {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Exclude);
+        self.predicate.accept_predicate(visitor);
+        visitor.leave_predicate(PredicateKind::Exclude);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_predicate(&mut self.predicate, rewriter);
+    }
+}
 
 impl ToExcludePredicate for super::SharedPredicate {
     fn exclude(&self) -> super::SharedPredicate {
@@ -1,6 +1,7 @@
 use super::super::expression;
 use super::super::sql;
 use super::super::field;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 use super::{ToSharedPredicate};
 
@@ -21,8 +22,24 @@ pub trait ToInPredicate<T> {
 }
 
 impl<F, T> super::Predicate for InPredicate<F, T>
-    where F: sql::ToPredicateValue,
-          T: sql::ToPredicateValue { }
+    where F: sql::ToPredicateValue + Clone + 'static,
+          T: sql::ToPredicateValue + Clone + 'static {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::In);
+        self.field.accept_value(visitor);
+        self.values.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::In);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.values.rewrite_value(rewriter);
+    }
+}
 
 impl<T> ToInPredicate<T> for field::NamedField<T> where T: sql::ToPredicateValue + Clone + 'static {
     fn in_<B: expression::ToListExpression<T> + sql::ToPredicateValue + Clone + 'static>(&self, val: B) -> super::SharedPredicate {
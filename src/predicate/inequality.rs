@@ -4,6 +4,7 @@ use super::{ToSharedPredicate};
 use super::super::expression;
 use super::super::field;
 use super::super::sql;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 #[derive(Clone, Copy)]
 pub enum Inequality {
@@ -41,8 +42,24 @@ pub trait ToInequalityPredicate<T> {
 }
 
 impl<F, T> super::Predicate for InequalityPredicate<F, T>
-    where F: sql::ToPredicateValue,
-          T: sql::ToPredicateValue { }
+    where F: sql::ToPredicateValue + Clone + 'static,
+          T: sql::ToPredicateValue + Clone + 'static {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Inequality);
+        self.field.accept_value(visitor);
+        self.value.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::Inequality);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.value.rewrite_value(rewriter);
+    }
+}
 
 macro_rules! impl_for {
     ($field:ty, $expr:ty) => (
@@ -1,6 +1,7 @@
 use super::super::sql;
 use super::super::expression;
 use super::super::field;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 use super::{ToSharedPredicate};
 
@@ -20,8 +21,24 @@ pub trait ToIsPredicate<T> {
 }
 
 impl<F, T> super::Predicate for IsPredicate<F, T>
-    where F: sql::ToPredicateValue,
-          T: sql::ToPredicateValue { }
+    where F: sql::ToPredicateValue + Clone + 'static,
+          T: sql::ToPredicateValue + Clone + 'static {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Is);
+        self.field.accept_value(visitor);
+        self.value.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::Is);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.value.rewrite_value(rewriter);
+    }
+}
 
 impl<T> ToIsPredicate<T> for field::NamedField<T> where T: sql::ToPredicateValue + Clone + 'static {
     fn is<B: expression::ToExpression<T> + sql::ToPredicateValue + Clone + 'static>(&self, val: B) -> super::SharedPredicate {
@@ -1,6 +1,7 @@
 use super::super::sql;
 use super::super::expression;
 use super::super::field;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 use super::{ToSharedPredicate};
 
@@ -20,7 +21,21 @@ pub trait ToIsNullPredicate {
     fn not_null(&self) -> super::SharedPredicate;
 }
 
-impl<F> super::Predicate for IsNullPredicate<F> where F: sql::ToPredicateValue {}
+impl<F> super::Predicate for IsNullPredicate<F> where F: sql::ToPredicateValue + Clone + 'static {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::IsNull);
+        self.field.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::IsNull);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+    }
+}
 
 impl<T> ToIsNullPredicate for field::NamedField<Option<T>> where T: sql::ToPredicateValue + Clone + 'static {
     fn is_null(&self) -> super::SharedPredicate {
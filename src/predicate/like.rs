@@ -1,6 +1,7 @@
 use super::super::expression;
 use super::super::field;
 use super::super::sql;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 use super::{ToSharedPredicate};
 
@@ -26,8 +27,24 @@ pub trait ToLikePredicate<T> {
 }
 
 impl<F, T> super::Predicate for LikePredicate<F, T>
-    where F: sql::ToPredicateValue,
-          T: sql::ToPredicateValue { }
+    where F: sql::ToPredicateValue + Clone + 'static,
+          T: sql::ToPredicateValue + Clone + 'static {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Like);
+        self.field.accept_value(visitor);
+        self.value.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::Like);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.value.rewrite_value(rewriter);
+    }
+}
 
 macro_rules! impl_for {
     ($field:ty, $expr:ty) => (
@@ -1,6 +1,7 @@
 use std::rc;
 
 use super::sql;
+use super::visitor::{Visitor, Rewriter};
 
 pub use self::raw::{RawPredicate};
 pub use self::is::{IsPredicate, ToIsPredicate};
@@ -35,7 +36,13 @@ pub mod like;
 pub mod raw;
 
 pub trait Predicate: sql::PredicateToSql {
+    fn upcast_predicate(&self) -> SharedPredicate;
 
+    /// Walks the predicate node and its operands.
+    fn accept_predicate(&self, visitor: &mut Visitor);
+
+    /// Rewrites subqueries of the operands.
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter);
 }
 
 pub trait ToSharedPredicate {
@@ -1,3 +1,4 @@
+use super::super::visitor::{self, Visitor, Rewriter, PredicateKind};
 use super::{ToSharedPredicate};
 
 #[derive(Clone)]
@@ -10,7 +11,23 @@ pub trait ToOrPredicate {
     fn or(&self, val: super::SharedPredicate) -> super::SharedPredicate;
 }
 
-impl super::Predicate for OrPredicate { }
+impl super::Predicate for OrPredicate {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Or);
+        self.left.accept_predicate(visitor);
+        self.right.accept_predicate(visitor);
+        visitor.leave_predicate(PredicateKind::Or);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        visitor::rewrite_shared_predicate(&mut self.left, rewriter);
+        visitor::rewrite_shared_predicate(&mut self.right, rewriter);
+    }
+}
 
 impl ToOrPredicate for super::SharedPredicate {
     fn or(&self, predicate: super::SharedPredicate) -> super::SharedPredicate {
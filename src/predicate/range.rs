@@ -2,6 +2,7 @@ use time;
 use super::super::sql;
 use super::super::expression;
 use super::super::field;
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
 
 use super::{ToSharedPredicate};
 
@@ -47,10 +48,28 @@ pub trait ToInRangePredicate<T> {
 }
 
 impl<F, T1, T2> super::Predicate for InRangePredicate<F, T1, T2>
-    where F: sql::ToPredicateValue,
-          T1: sql::ToPredicateValue,
-          T2: sql::ToPredicateValue
-    { }
+    where F: sql::ToPredicateValue + Clone + 'static,
+          T1: sql::ToPredicateValue + Clone + 'static,
+          T2: sql::ToPredicateValue + Clone + 'static
+    {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::InRange);
+        self.field.accept_value(visitor);
+        self.from.accept_value(visitor);
+        self.to.accept_value(visitor);
+        visitor.leave_predicate(PredicateKind::InRange);
+    }
+
+    fn rewrite_predicate(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.from.rewrite_value(rewriter);
+        self.to.rewrite_value(rewriter);
+    }
+}
 
 macro_rules! impl_for {
     ($field:ty, $expr:ty) => (
@@ -1,3 +1,6 @@
+use super::super::visitor::{Visitor, Rewriter, PredicateKind};
+use super::{ToSharedPredicate};
+
 #[derive(Clone)]
 pub struct RawPredicate {
     pub content: String
@@ -9,4 +12,16 @@ impl RawPredicate {
     }
 }
 
-impl super::Predicate for RawPredicate { }
+impl super::Predicate for RawPredicate {
+    fn upcast_predicate(&self) -> super::SharedPredicate {
+        self.clone().upcast()
+    }
+
+    fn accept_predicate(&self, visitor: &mut Visitor) {
+        visitor.visit_predicate(PredicateKind::Raw);
+        visitor.visit_raw(&self.content);
+        visitor.leave_predicate(PredicateKind::Raw);
+    }
+
+    fn rewrite_predicate(&mut self, _rewriter: &mut Rewriter) {}
+}
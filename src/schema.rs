@@ -0,0 +1,167 @@
+use serialize::json;
+use time;
+use uuid;
+
+use super::from;
+use super::field;
+use super::sql;
+
+/// Database-independent column type, adapters map it to their own type names.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColumnType {
+    Boolean,
+    TinyInt,
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    Text,
+    Bytes,
+    Json,
+    Timestamp,
+    Uuid
+}
+
+/// Rust types that have a column type.
+pub trait ToColumnType {
+    fn column_type() -> ColumnType;
+    fn is_nullable() -> bool { false }
+}
+
+macro_rules! to_column_type {
+    ($t:ty, $column_type:expr) => (
+        impl ToColumnType for $t {
+            fn column_type() -> ColumnType { $column_type }
+        }
+    )
+}
+
+to_column_type!(bool, ColumnType::Boolean);
+to_column_type!(i8, ColumnType::TinyInt);
+to_column_type!(i16, ColumnType::SmallInt);
+to_column_type!(i32, ColumnType::Integer);
+to_column_type!(i64, ColumnType::BigInt);
+to_column_type!(f32, ColumnType::Real);
+to_column_type!(f64, ColumnType::Double);
+to_column_type!(String, ColumnType::Text);
+to_column_type!(Vec<u8>, ColumnType::Bytes);
+to_column_type!(json::Json, ColumnType::Json);
+to_column_type!(time::Timespec, ColumnType::Timestamp);
+to_column_type!(uuid::Uuid, ColumnType::Uuid);
+
+impl<T: ToColumnType> ToColumnType for Option<T> {
+    fn column_type() -> ColumnType { T::column_type() }
+    fn is_nullable() -> bool { true }
+}
+
+#[derive(Clone)]
+pub struct ColumnDef {
+    name: String,
+    column_type: ColumnType,
+    nullable: bool,
+    default: Option<sql::SharedLiteral>,
+    primary_key: bool,
+    unique: bool,
+    references: Option<(String, String)>
+}
+
+impl ColumnDef {
+    /// Column definition for a field, type and nullability come from `T`.
+    pub fn of<T: ToColumnType + Clone + 'static>(field: &field::NamedField<T>) -> ColumnDef {
+        ColumnDef {
+            name: field.name.to_string(),
+            column_type: T::column_type(),
+            nullable: T::is_nullable(),
+            default: None,
+            primary_key: false,
+            unique: false,
+            references: None
+        }
+    }
+
+    pub fn get_name(&self) -> &String { &self.name }
+    pub fn get_column_type(&self) -> &ColumnType { &self.column_type }
+    pub fn is_nullable(&self) -> bool { self.nullable }
+    pub fn get_default(&self) -> &Option<sql::SharedLiteral> { &self.default }
+    pub fn is_primary_key(&self) -> bool { self.primary_key }
+    pub fn is_unique(&self) -> bool { self.unique }
+    pub fn get_references(&self) -> &Option<(String, String)> { &self.references }
+
+    pub fn default(&self, value: &sql::ToSqlLiteral) -> ColumnDef {
+        with_clone!(self, column, column.default = Some(value.upcast_literal()))
+    }
+
+    pub fn primary_key(&self) -> ColumnDef {
+        with_clone!(self, column, column.primary_key = true)
+    }
+
+    pub fn unique(&self) -> ColumnDef {
+        with_clone!(self, column, column.unique = true)
+    }
+
+    pub fn references(&self, field: &field::Field) -> ColumnDef {
+        with_clone!(self, column, column.references = Some((field.table_name().to_string(), field.name().to_string())))
+    }
+}
+
+#[derive(Clone)]
+pub struct TableSchema {
+    table: from::SharedTable,
+    columns: Vec<ColumnDef>,
+    primary_key: Vec<String>,
+    unique: Vec<Vec<String>>
+}
+
+impl TableSchema {
+    pub fn new(table: &from::Table) -> TableSchema {
+        TableSchema {
+            table: table.upcast_table(),
+            columns: vec![],
+            primary_key: vec![],
+            unique: vec![]
+        }
+    }
+
+    pub fn get_table(&self) -> &from::SharedTable { &self.table }
+    pub fn get_columns(&self) -> &Vec<ColumnDef> { &self.columns }
+    pub fn get_primary_key(&self) -> &Vec<String> { &self.primary_key }
+    pub fn get_unique(&self) -> &Vec<Vec<String>> { &self.unique }
+
+    pub fn column(mut self, column: ColumnDef) -> TableSchema {
+        self.columns.push(column);
+        self
+    }
+
+    /// Table-level (possibly composite) primary key.
+    pub fn primary_key(mut self, fields: &[&field::Field]) -> TableSchema {
+        self.primary_key = fields.iter().map(|f| f.name().to_string()).collect();
+        self
+    }
+
+    /// Table-level (possibly composite) unique constraint.
+    pub fn unique(mut self, fields: &[&field::Field]) -> TableSchema {
+        self.unique.push(fields.iter().map(|f| f.name().to_string()).collect());
+        self
+    }
+
+    pub fn create(&self) -> CreateTableQuery {
+        CreateTableQuery { schema: self.clone(), if_not_exists: false }
+    }
+}
+
+#[derive(Clone)]
+pub struct CreateTableQuery {
+    schema: TableSchema,
+    if_not_exists: bool
+}
+
+impl CreateTableQuery {
+    pub fn get_schema(&self) -> &TableSchema { &self.schema }
+    pub fn is_if_not_exists(&self) -> bool { self.if_not_exists }
+
+    pub fn if_not_exists(mut self) -> CreateTableQuery {
+        self.if_not_exists = true;
+        self
+    }
+}
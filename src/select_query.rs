@@ -6,10 +6,12 @@ use super::from;
 use super::expression::{self, UntypedExpression};
 use super::predicate::{self, ToOrPredicate, ToAndPredicate, ToExcludePredicate};
 use super::sql;
+use super::field;
 use super::order_by;
 use super::join;
 use super::distinct;
 use super::group_by;
+use super::visitor::{Visitor, Visitable, Rewriter, Rewritable};
 
 #[derive(Clone)]
 pub enum Select {
@@ -31,12 +33,55 @@ impl<T> ToSelectQuery for T where T: AbstractSelectQuery + Clone + 'static {
     }
 }
 
-#[derive(Clone, Copy)]
-pub enum SelectFor {
+#[derive(Clone, Copy, PartialEq)]
+pub enum LockStrength {
     Update,
-    UpdateNoWait,
+    NoKeyUpdate,
     Share,
-    ShareNoWait
+    KeyShare
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LockWait {
+    Wait,
+    NoWait,
+    SkipLocked
+}
+
+/// Row locking clause, e.g. `FOR UPDATE OF jedi SKIP LOCKED`.
+#[derive(Clone)]
+pub struct SelectFor {
+    strength: LockStrength,
+    of: Vec<from::SharedTable>,
+    wait: LockWait
+}
+
+impl SelectFor {
+    pub fn new(strength: LockStrength) -> SelectFor {
+        SelectFor { strength: strength, of: vec![], wait: LockWait::Wait }
+    }
+
+    pub fn update() -> SelectFor { SelectFor::new(LockStrength::Update) }
+    pub fn no_key_update() -> SelectFor { SelectFor::new(LockStrength::NoKeyUpdate) }
+    pub fn share() -> SelectFor { SelectFor::new(LockStrength::Share) }
+    pub fn key_share() -> SelectFor { SelectFor::new(LockStrength::KeyShare) }
+
+    pub fn get_strength(&self) -> &LockStrength { &self.strength }
+    pub fn get_of(&self) -> &Vec<from::SharedTable> { &self.of }
+    pub fn get_wait(&self) -> &LockWait { &self.wait }
+
+    /// Locks rows of the listed tables only.
+    pub fn of(&self, tables: &[&from::Table]) -> SelectFor {
+        with_clone!(self, lock, lock.of.extend(tables.iter().map(|t| t.upcast_table())))
+    }
+
+    pub fn nowait(&self) -> SelectFor {
+        with_clone!(self, lock, lock.wait = LockWait::NoWait)
+    }
+
+    pub fn skip_locked(&self) -> SelectFor {
+        with_clone!(self, lock, lock.wait = LockWait::SkipLocked)
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -139,6 +184,19 @@ predicate_trait!(
     or_exclude_having
 );
 
+predicate_trait!(
+    HasFilter,
+    get_filter,
+    set_filter,
+    unset_filter,
+    filter,
+    and_filter,
+    or_filter,
+    exclude_filter,
+    and_exclude_filter,
+    or_exclude_filter
+);
+
 pub trait Orderable: Clone {
     fn get_order_by_mut(&mut self) -> &mut Vec<order_by::OrderBy>;
     fn set_order_by(&mut self, Vec<order_by::OrderBy>);
@@ -201,7 +259,7 @@ pub struct SelectQuery<T, L, M> {
     limit: Option<usize>,
     offset: Option<usize>,
     order_by: Vec<order_by::OrderBy>,
-    for_: Option<SelectFor>,
+    for_: Vec<SelectFor>,
 
     _marker_t: marker::PhantomData<T>,
     _marker_l: marker::PhantomData<L>,
@@ -220,7 +278,40 @@ impl<T, L, M> SelectQuery<T, L, M> {
     pub fn get_limit(&self) -> &Option<usize> { &self.limit }
     pub fn get_offset(&self) -> &Option<usize> { &self.offset }
     pub fn get_order_by(&self) -> &Vec<order_by::OrderBy> { &self.order_by }
-    pub fn get_for(&self) -> &Option<SelectFor> { &self.for_ }
+    pub fn get_for(&self) -> &Vec<SelectFor> { &self.for_ }
+
+    pub fn get_distinct_mut(&mut self) -> &mut Option<distinct::Distinct> { &mut self.distinct }
+    pub fn get_select_mut(&mut self) -> &mut Select { &mut self.select }
+    pub fn get_from_mut(&mut self) -> &mut from::SharedFrom { &mut self.from }
+    pub fn get_where_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { &mut self.where_ }
+    pub fn get_group_by_mut(&mut self) -> &mut Option<group_by::GroupBy> { &mut self.group_by }
+    pub fn get_having_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { &mut self.having }
+}
+
+/// Whether one side of a join has the column of `field`: `Some(true)` if the field belongs to one of
+/// `sources` or is among their known output columns, `None` if their columns are unknown.
+fn side_has_field(sources: &[&from::From], field: &field::Field) -> Option<bool> {
+    let mut result = Some(false);
+    for source in sources.iter() {
+        let names = source.from_names();
+        let has = if names.iter().any(|n| &n[..] == field.table_name() || Some(n) == field.qual()) {
+            Some(true)
+        } else {
+            match source.column_names() {
+                Some(columns) => Some(columns.iter().any(|c| &c[..] == field.name())),
+                // Sources without names (e.g. custom From implementations) can't be checked
+                None if names.is_empty() => Some(true),
+                None => None
+            }
+        };
+
+        match has {
+            Some(true) => return Some(true),
+            None => result = None,
+            Some(false) => ()
+        }
+    }
+    result
 }
 
 
@@ -241,7 +332,7 @@ impl<T: Clone, L: Clone, M: Clone> SelectQuery<T, L, M> {
             limit: None,
             offset: None,
             order_by: vec![],
-            for_: None,
+            for_: vec![],
 
             _marker_t: marker::PhantomData,
             _marker_l: marker::PhantomData,
@@ -258,13 +349,31 @@ impl<T: Clone, L: Clone, M: Clone> SelectQuery<T, L, M> {
     }
 
     pub fn group_by(&self, fields: &[&expression::UntypedExpression]) -> SelectQuery<T, L, M> {
-        with_clone!(self, query, query.group_by = Some(group_by::GroupBy::by(fields)))
+        with_clone!(self, query, query.group_by = Some(self.grouping().replace_by(fields)))
     }
 
-    pub fn limit(&self, limit: usize) -> SelectQuery<T, LimitOne, M> {
-        let mut query = self.clone();
-        query.limit = Some(limit);
-        unsafe{ mem::transmute(query) }
+    fn grouping(&self) -> group_by::GroupBy {
+        self.group_by.clone().unwrap_or(group_by::GroupBy::new())
+    }
+
+    /// Adds `ROLLUP (...)` to the GROUP BY clause.
+    pub fn group_by_rollup(&self, fields: &[&expression::UntypedExpression]) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.group_by = Some(self.grouping().rollup(fields)))
+    }
+
+    /// Adds `CUBE (...)` to the GROUP BY clause.
+    pub fn group_by_cube(&self, fields: &[&expression::UntypedExpression]) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.group_by = Some(self.grouping().cube(fields)))
+    }
+
+    /// Adds `GROUPING SETS (...)` to the GROUP BY clause.
+    pub fn group_by_grouping_sets(&self, sets: &[&[&expression::UntypedExpression]]) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.group_by = Some(self.grouping().grouping_sets(sets)))
+    }
+
+    /// Keeps the cardinality of the query, only `first()` makes it return a single row.
+    pub fn limit(&self, limit: usize) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.limit = Some(limit))
     }
 
     pub fn first(&self) -> SelectQuery<T, LimitOne, M> {
@@ -285,20 +394,50 @@ impl<T: Clone, L: Clone, M: Clone> SelectQuery<T, L, M> {
         self.alias(alias)
     }
 
+    /// Sets the row locking clause, replacing previous ones.
+    pub fn lock(&self, lock: SelectFor) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.for_ = vec![lock])
+    }
+
+    /// Adds one more row locking clause, e.g. to lock joined tables with another strength.
+    pub fn add_lock(&self, lock: SelectFor) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.for_.push(lock))
+    }
+
+    pub fn unlock(&self) -> SelectQuery<T, L, M> {
+        with_clone!(self, query, query.for_ = vec![])
+    }
+
     pub fn for_update(&self) -> SelectQuery<T, L, M> {
-        with_clone!(self, query, query.for_ = Some(SelectFor::Update))
+        self.lock(SelectFor::update())
     }
 
     pub fn for_update_nowait(&self) -> SelectQuery<T, L, M> {
-        with_clone!(self, query, query.for_ = Some(SelectFor::UpdateNoWait))
+        self.lock(SelectFor::update().nowait())
+    }
+
+    pub fn for_update_skip_locked(&self) -> SelectQuery<T, L, M> {
+        self.lock(SelectFor::update().skip_locked())
+    }
+
+    pub fn for_no_key_update(&self) -> SelectQuery<T, L, M> {
+        self.lock(SelectFor::no_key_update())
     }
 
     pub fn for_share(&self) -> SelectQuery<T, L, M> {
-        with_clone!(self, query, query.for_ = Some(SelectFor::Share))
+        self.lock(SelectFor::share())
     }
 
     pub fn for_share_nowait(&self) -> SelectQuery<T, L, M> {
-        with_clone!(self, query, query.for_ = Some(SelectFor::ShareNoWait))
+        self.lock(SelectFor::share().nowait())
+    }
+
+    pub fn for_key_share(&self) -> SelectQuery<T, L, M> {
+        self.lock(SelectFor::key_share())
+    }
+
+    pub fn get_joins_mut(&mut self) -> &mut Vec<join::Join> {
+        &mut self.joins
     }
 
     pub fn inner_join(&self, from: &from::From, on: predicate::SharedPredicate) -> SelectQuery<T, L, M> {
@@ -349,6 +488,70 @@ impl<T: Clone, L: Clone, M: Clone> SelectQuery<T, L, M> {
         with_clone!(self, query, query.joins.push(join::Join::cross_join(from.upcast_from())))
     }
 
+    fn using_join(&self, join_type: join::ConditionedJoinType, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        let mut left: Vec<&from::From> = vec![&**self.from];
+        left.extend(self.joins.iter().map(|join| &***join.get_from()));
+        let right: Vec<&from::From> = vec![from];
+
+        let mut using: Vec<field::SharedField> = vec![];
+        for field in fields.iter() {
+            let (on_left, on_right) = (side_has_field(&left, *field), side_has_field(&right, *field));
+            if on_left == Some(false) || on_right == Some(false) || (on_left.is_none() && on_right.is_none()) {
+                return Err(join::UsingError::UnknownField(field.name().to_string()))
+            }
+
+            if using.iter().any(|f| f.name() == field.name()) {
+                return Err(join::UsingError::DuplicateField(field.name().to_string()))
+            }
+
+            using.push(field.upcast_field());
+        }
+
+        if using.is_empty() {
+            return Err(join::UsingError::NoFields)
+        }
+
+        Ok(with_clone!(self, query, query.joins.push(join::Join::using(join_type, from.upcast_from(), using))))
+    }
+
+    /// Joins on equal `fields` of both sides, merged into one column each.
+    ///
+    /// The merged column is referenced unqualified (`field.unqual()`), a qualified field still
+    /// refers to the column of its table, which is NULL on the missing side of outer joins.
+    pub fn join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.inner_join_using(from, fields)
+    }
+
+    pub fn inner_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::InnerJoin, from, fields)
+    }
+
+    /// See `join_using` for referencing the merged columns.
+    pub fn full_outer_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::FullOuterJoin, from, fields)
+    }
+
+    pub fn right_outer_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::RightOuterJoin, from, fields)
+    }
+
+    pub fn left_outer_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::LeftOuterJoin, from, fields)
+    }
+
+    /// See `join_using` for referencing the merged columns.
+    pub fn full_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::FullJoin, from, fields)
+    }
+
+    pub fn left_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::LeftJoin, from, fields)
+    }
+
+    pub fn right_join_using(&self, from: &from::From, fields: &[&field::Field]) -> Result<SelectQuery<T, L, M>, join::UsingError> {
+        self.using_join(join::ConditionedJoinType::RightJoin, from, fields)
+    }
+
     pub fn unjoin(&self) -> SelectQuery<T, L, M> {
         with_clone!(self, query, query.joins = vec![])
     }
@@ -408,6 +611,14 @@ impl<T: Clone + 'static, L: Clone + 'static, M: Clone + 'static> expression::Unt
     fn upcast_expression(&self) -> expression::SharedExpression {
         rc::Rc::new(Box::new(self.clone()) as expression::BoxedExpression)
     }
+
+    fn accept_expression(&self, visitor: &mut Visitor) {
+        self.accept(visitor)
+    }
+
+    fn rewrite_expression(&mut self, rewriter: &mut Rewriter) {
+        self.rewrite(rewriter)
+    }
 }
 
 impl<M: Clone + 'static, T: Clone + 'static> expression::Expression<T> for SelectQuery<(T,), LimitOne, M> { }
@@ -1,21 +1,226 @@
+use super::super::schema;
+
 pub trait SqlAdapter {
+    fn name(&self) -> &'static str { "SQL adapter" }
     fn placeholder(&self, idx: u8) -> String;
+
+    fn column_type(&self, column_type: &schema::ColumnType) -> String {
+        match column_type {
+            &schema::ColumnType::Boolean => "BOOLEAN",
+            &schema::ColumnType::TinyInt => "SMALLINT",
+            &schema::ColumnType::SmallInt => "SMALLINT",
+            &schema::ColumnType::Integer => "INTEGER",
+            &schema::ColumnType::BigInt => "BIGINT",
+            &schema::ColumnType::Real => "REAL",
+            &schema::ColumnType::Double => "DOUBLE PRECISION",
+            &schema::ColumnType::Text => "TEXT",
+            &schema::ColumnType::Bytes => "BYTEA",
+            &schema::ColumnType::Json => "JSON",
+            &schema::ColumnType::Timestamp => "TIMESTAMP WITH TIME ZONE",
+            &schema::ColumnType::Uuid => "UUID",
+        }.to_string()
+    }
+
+    /// Whether a column of this type can be part of a key or have a DEFAULT.
+    fn supports_keyed_column(&self, _column_type: &schema::ColumnType) -> bool { true }
+
+    /// Quoted string, standard SQL only escapes the quote.
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace("'", "''"))
+    }
+
+    fn bytes_literal(&self, value: &[u8]) -> String {
+        format!("X'{}'", hex(value))
+    }
+
+    /// Literal of NaN or an infinity of a double (or single) precision column, `None` if there is none.
+    fn special_float_literal(&self, _value: f64, _double: bool) -> Option<String> { None }
+
+    /// Statements taking and releasing a session-level lock named `name`, `None` if there is none.
+    fn advisory_lock(&self, _name: &str) -> Option<(String, String)> { None }
+
+    fn supports_grouping_sets(&self) -> bool { true }
+    fn supports_with_rollup(&self) -> bool { false }
+    fn supports_aggregate_filter(&self) -> bool { true }
+    fn supports_ordered_aggregates(&self) -> bool { true }
+    /// BOOL_AND and BOOL_OR, otherwise MIN and MAX of the booleans stored as integers.
+    fn supports_bool_aggregates(&self) -> bool { true }
+    /// STRING_AGG is GROUP_CONCAT(... SEPARATOR '...').
+    fn uses_group_concat(&self) -> bool { false }
+    fn supports_order_limit_in_modify(&self) -> bool { false }
+    /// FOR UPDATE and FOR SHARE, SQLite locks the whole database instead.
+    fn supports_row_locks(&self) -> bool { true }
+    fn supports_key_locks(&self) -> bool { true }
+    /// STDDEV and VARIANCE are cast to DOUBLE PRECISION, PostgreSQL returns NUMERIC for integers.
+    fn casts_statistics_to_double(&self) -> bool { true }
+    fn supports_truncate(&self) -> bool { true }
+    /// Several tables in one TRUNCATE, otherwise every table gets its own statement.
+    fn supports_truncate_tables(&self) -> bool { true }
+    fn supports_truncate_only(&self) -> bool { true }
+    fn supports_truncate_identity(&self) -> bool { true }
+    fn supports_truncate_cascade(&self) -> bool { true }
+    fn supports_alter_column(&self) -> bool { true }
+    fn alters_column_with_modify(&self) -> bool { false }
+    fn supports_index_options(&self) -> bool { true }
+    fn supports_partial_indexes(&self) -> bool { true }
+    fn supports_transaction_modes(&self) -> bool { true }
+    fn supports_deferrable_transactions(&self) -> bool { true }
+    /// Isolation level is set with SET TRANSACTION before START TRANSACTION.
+    fn sets_transaction_isolation(&self) -> bool { false }
+
+    /// Hidden column identifying a row, used to emulate ORDER BY and LIMIT in UPDATE and DELETE.
+    fn row_identifier(&self) -> Option<&'static str> { None }
+}
+
+fn hex(value: &[u8]) -> String {
+    let hex: Vec<String> = value.iter().map(|b| format!("{:02x}", b)).collect();
+    hex.concat()
 }
 
 #[derive(Copy, Clone)]
 pub struct PostgreSqlAdapter;
 
 impl SqlAdapter for PostgreSqlAdapter {
+    fn name(&self) -> &'static str {
+        "PostgreSQL"
+    }
+
     fn placeholder(&self, idx: u8) -> String {
         format!("${}", idx)
     }
+
+    /// `X'...'` is a bit string in PostgreSQL.
+    fn bytes_literal(&self, value: &[u8]) -> String {
+        format!("'\\x{}'::bytea", hex(value))
+    }
+
+    fn special_float_literal(&self, value: f64, double: bool) -> Option<String> {
+        let literal = if value.is_nan() {
+            "NaN"
+        } else if value > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        };
+        Some(format!("'{}'::{}", literal, if double { "float8" } else { "float4" }))
+    }
+
+    fn advisory_lock(&self, name: &str) -> Option<(String, String)> {
+        let key = format!("hashtext({})", self.string_literal(name));
+        Some((format!("SELECT pg_advisory_lock({});", key), format!("SELECT pg_advisory_unlock({});", key)))
+    }
+
+    fn row_identifier(&self) -> Option<&'static str> { Some("ctid") }
 }
 
 #[derive(Copy, Clone)]
 pub struct MysqlAdapter;
 
 impl SqlAdapter for MysqlAdapter {
+    fn name(&self) -> &'static str {
+        "MySQL"
+    }
+
     fn placeholder(&self, _: u8) -> String {
         "?".to_owned()
     }
-}
\ No newline at end of file
+
+    fn column_type(&self, column_type: &schema::ColumnType) -> String {
+        match column_type {
+            &schema::ColumnType::Boolean => "BOOLEAN",
+            &schema::ColumnType::TinyInt => "TINYINT",
+            &schema::ColumnType::SmallInt => "SMALLINT",
+            &schema::ColumnType::Integer => "INT",
+            &schema::ColumnType::BigInt => "BIGINT",
+            &schema::ColumnType::Real => "FLOAT",
+            &schema::ColumnType::Double => "DOUBLE",
+            &schema::ColumnType::Text => "TEXT",
+            &schema::ColumnType::Bytes => "BLOB",
+            &schema::ColumnType::Json => "JSON",
+            &schema::ColumnType::Timestamp => "TIMESTAMP",
+            &schema::ColumnType::Uuid => "CHAR(36)",
+        }.to_string()
+    }
+
+    /// TEXT and BLOB columns can't be keys without a prefix length nor take a DEFAULT before 8.0.13.
+    fn supports_keyed_column(&self, column_type: &schema::ColumnType) -> bool {
+        match column_type {
+            &schema::ColumnType::Text | &schema::ColumnType::Bytes => false,
+            _ => true
+        }
+    }
+
+    /// Backslash escapes in the default SQL mode, doubled it stays inside the literal with
+    /// NO_BACKSLASH_ESCAPES as well.
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace("\\", "\\\\").replace("'", "''"))
+    }
+
+    /// Negative timeout waits until the lock is released.
+    fn advisory_lock(&self, name: &str) -> Option<(String, String)> {
+        let name = self.string_literal(name);
+        Some((format!("SELECT GET_LOCK({}, -1);", name), format!("SELECT RELEASE_LOCK({});", name)))
+    }
+
+    fn supports_grouping_sets(&self) -> bool { false }
+    fn supports_with_rollup(&self) -> bool { true }
+    fn supports_aggregate_filter(&self) -> bool { false }
+    fn supports_ordered_aggregates(&self) -> bool { false }
+    fn supports_bool_aggregates(&self) -> bool { false }
+    fn uses_group_concat(&self) -> bool { true }
+    fn supports_order_limit_in_modify(&self) -> bool { true }
+    fn supports_key_locks(&self) -> bool { false }
+    fn casts_statistics_to_double(&self) -> bool { false }
+    fn supports_truncate_tables(&self) -> bool { false }
+    fn supports_truncate_only(&self) -> bool { false }
+    fn supports_truncate_identity(&self) -> bool { false }
+    fn supports_truncate_cascade(&self) -> bool { false }
+    fn alters_column_with_modify(&self) -> bool { true }
+    fn supports_index_options(&self) -> bool { false }
+    fn supports_partial_indexes(&self) -> bool { false }
+    fn supports_deferrable_transactions(&self) -> bool { false }
+    fn sets_transaction_isolation(&self) -> bool { true }
+}
+
+#[derive(Copy, Clone)]
+pub struct SqliteAdapter;
+
+impl SqlAdapter for SqliteAdapter {
+    fn name(&self) -> &'static str {
+        "SQLite"
+    }
+
+    fn placeholder(&self, idx: u8) -> String {
+        format!("?{}", idx)
+    }
+
+    // SQLite uses type affinities instead of strict types
+    fn column_type(&self, column_type: &schema::ColumnType) -> String {
+        match column_type {
+            &schema::ColumnType::Boolean |
+            &schema::ColumnType::TinyInt |
+            &schema::ColumnType::SmallInt |
+            &schema::ColumnType::Integer |
+            &schema::ColumnType::BigInt => "INTEGER",
+            &schema::ColumnType::Real |
+            &schema::ColumnType::Double => "REAL",
+            &schema::ColumnType::Bytes => "BLOB",
+            &schema::ColumnType::Text |
+            &schema::ColumnType::Json |
+            &schema::ColumnType::Timestamp |
+            &schema::ColumnType::Uuid => "TEXT",
+        }.to_string()
+    }
+
+    fn supports_grouping_sets(&self) -> bool { false }
+    fn supports_ordered_aggregates(&self) -> bool { false }
+    fn supports_bool_aggregates(&self) -> bool { false }
+    fn supports_row_locks(&self) -> bool { false }
+    fn supports_key_locks(&self) -> bool { false }
+    fn supports_truncate(&self) -> bool { false }
+    fn supports_alter_column(&self) -> bool { false }
+    fn supports_index_options(&self) -> bool { false }
+    fn supports_transaction_modes(&self) -> bool { false }
+    fn supports_deferrable_transactions(&self) -> bool { false }
+    fn row_identifier(&self) -> Option<&'static str> { Some("rowid") }
+}
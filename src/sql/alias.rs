@@ -0,0 +1,7 @@
+use super::super::alias;
+
+impl<T: Clone> super::ToSql for alias::AliasedExpression<T> {
+    fn to_sql(&self, _ctx: &mut super::SqlContext) -> String {
+        self.get_alias().to_string()
+    }
+}
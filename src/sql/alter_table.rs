@@ -0,0 +1,92 @@
+use super::super::alter_table_query::{AlterTableQuery, AlterAction, Constraint};
+use super::super::from::{Table};
+use super::{ToSql};
+
+impl super::ToSql for Constraint {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        match self {
+            &Constraint::PrimaryKey(ref cols) => format!("PRIMARY KEY ({})", cols.connect(", ")),
+            &Constraint::Unique(ref cols) => format!("UNIQUE ({})", cols.connect(", ")),
+            &Constraint::ForeignKey(ref cols, ref table, ref ref_cols) => {
+                if cols.is_empty() || cols.len() != ref_cols.len() || table.is_empty() {
+                    ctx.error(super::SqlError::Invalid(
+                        "FOREIGN KEY needs as many referenced columns of a single table as key columns".to_string()
+                    ));
+                }
+                format!("FOREIGN KEY ({}) REFERENCES {} ({})", cols.connect(", "), table, ref_cols.connect(", "))
+            }
+        }
+    }
+}
+
+impl super::ToSql for AlterAction {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        let alter_column = ctx.adapter().supports_alter_column();
+        let modify = ctx.adapter().alters_column_with_modify();
+
+        match self {
+            &AlterAction::AddColumn(ref column) => format!("ADD COLUMN {}", column.to_sql(ctx)),
+            &AlterAction::DropColumn(ref name) => format!("DROP COLUMN {}", name),
+            &AlterAction::RenameColumn(ref name, ref new_name) => format!("RENAME COLUMN {} TO {}", name, new_name),
+            &AlterAction::RenameTo(ref name) => format!("RENAME TO {}", name),
+            _ if !alter_column => {
+                ctx.unsupported("ALTER TABLE with anything but ADD, DROP and RENAME COLUMN or RENAME TO");
+                String::new()
+            },
+            &AlterAction::ModifyColumn(ref column) if modify => format!("MODIFY COLUMN {}", column.to_sql(ctx)),
+            &AlterAction::ModifyColumn(ref column) => {
+                let name = column.get_name();
+                let mut actions = vec![
+                    format!("ALTER COLUMN {} TYPE {}", name, ctx.adapter().column_type(column.get_column_type())),
+                    format!("ALTER COLUMN {} {}", name, if column.is_nullable() { "DROP NOT NULL" } else { "SET NOT NULL" })
+                ];
+                actions.push(match column.get_default() {
+                    &Some(ref default) => format!("ALTER COLUMN {} SET DEFAULT {}", name, default.to_sql_literal(ctx)),
+                    &None => format!("ALTER COLUMN {} DROP DEFAULT", name)
+                });
+                actions.connect(", ")
+            },
+            // MODIFY COLUMN restates the whole definition, a partial one would drop DEFAULT and AUTO_INCREMENT
+            &AlterAction::SetType(..) | &AlterAction::SetNotNull(..) | &AlterAction::DropNotNull(..) if modify => {
+                ctx.unsupported("changing column type or nullability alone, use modify_column");
+                String::new()
+            },
+            &AlterAction::SetType(ref name, ref column_type) => {
+                format!("ALTER COLUMN {} TYPE {}", name, ctx.adapter().column_type(column_type))
+            },
+            &AlterAction::SetDefault(ref name, ref default) => format!("ALTER COLUMN {} SET DEFAULT {}", name, default.to_sql_literal(ctx)),
+            &AlterAction::DropDefault(ref name) => format!("ALTER COLUMN {} DROP DEFAULT", name),
+            &AlterAction::SetNotNull(ref name) => format!("ALTER COLUMN {} SET NOT NULL", name),
+            &AlterAction::DropNotNull(ref name) => format!("ALTER COLUMN {} DROP NOT NULL", name),
+            &AlterAction::AddConstraint(ref name, ref constraint) => format!("ADD CONSTRAINT {} {}", name, constraint.to_sql(ctx)),
+            &AlterAction::DropConstraint(ref name) => format!("DROP CONSTRAINT {}", name),
+        }
+    }
+}
+
+impl super::StatementsToSql for AlterTableQuery {
+    fn to_statements(&self, ctx: &mut super::SqlContext) -> Vec<String> {
+        // One statement per action, renames can't be combined with other actions in PostgreSQL
+        // and SQLite allows a single action only.
+        let mut table = self.get_table().get_table_name().to_string();
+        self.get_actions().iter().map(|action| {
+            let sql = format!("ALTER TABLE {} {}", table, action.to_sql(ctx));
+            if let &AlterAction::RenameTo(ref name) = action {
+                table = name.to_string();
+            }
+            sql
+        }).collect()
+    }
+}
+
+impl super::ToSql for AlterTableQuery {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        super::statements_to_sql(self, ctx)
+    }
+}
+
+impl super::QueryToSql for AlterTableQuery {
+    fn try_to_final_statements(&self, ctx: &mut super::SqlContext) -> Result<Vec<String>, super::SqlError> {
+        super::statements_to_final_sql(self, ctx)
+    }
+}
@@ -1,5 +1,6 @@
 use super::super::delete_query;
 use super::from::{FromToSql};
+use super::modify;
 
 impl<T, L, M> super::ToSql for delete_query::DeleteQuery<T, L, M> {
     fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
@@ -11,23 +12,16 @@ impl<T, L, M> super::ToSql for delete_query::DeleteQuery<T, L, M> {
 
         sql = format!("{} {}", sql, self.get_table().to_from_sql(ctx));
 
-        if self.get_using().is_some() {
-            let using = self.get_using().as_ref().unwrap();
-            if !using.is_empty() {
-                let tables_str: Vec<String> = using.iter().map(|v| v.as_sql().to_from_sql(ctx)).collect();
-                sql = format!("{} USING {}", sql, tables_str.connect(", "))
-            }
-        }
-
-        match self.get_where().as_ref() {
-            Some(predicate) => {
-                sql = format!("{} WHERE {}", sql, predicate.to_sql(false, ctx))
-            },
-            None if !self.is_all() => {
-                // http://devopsreactions.tumblr.com/post/47352638154/almost-ran-update-without-where
-                sql = format!("{} WHERE true = false", sql)
-            },
-            _ => ()
+        let ordered = !self.get_order_by().is_empty() || self.get_limit().is_some();
+        if ordered && !ctx.adapter().supports_order_limit_in_modify() {
+            sql = format!("{}{}", sql, modify::emulated_order_limit_to_sql(
+                "USING", self.get_table(), self.get_using(), self.get_where(), self.is_all(),
+                self.get_order_by(), self.get_limit(), ctx
+            ));
+        } else {
+            sql = format!("{}{}", sql, modify::sources_to_sql("USING", self.get_using(), ctx));
+            sql = format!("{}{}", sql, modify::where_to_sql(self.get_where(), self.is_all(), ctx));
+            sql = format!("{}{}", sql, modify::order_limit_to_sql(self.get_using(), self.get_order_by(), self.get_limit(), ctx));
         }
 
         match self.get_returning() {
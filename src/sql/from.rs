@@ -23,3 +23,9 @@ impl FromToSql for from::SharedTable {
         }
     }
 }
+
+impl FromToSql for from::Lateral {
+    fn to_from_sql(&self, ctx: &mut super::SqlContext) -> String {
+        format!("LATERAL {}", self.get_from().as_sql().to_from_sql(ctx))
+    }
+}
@@ -5,27 +5,198 @@ use function::{
     Max, MaxArg,
     Avg, AvgArg,
     Count, CountArg,
-    CountAll
+    BoolAnd, BoolAndArg,
+    BoolOr, BoolOrArg,
+    ArrayAgg, ArrayAggArg,
+    JsonAgg, JsonAggArg,
+    Stddev, StddevArg,
+    Variance, VarianceArg,
+    StringAgg, StringAggArg,
+    PercentileCont, PercentileContArg,
+    JsonObjectAgg,
+    CountAll,
+    AggregateModifiers,
+    Grouping,
+    TableFunction
 };
 
+use expression;
+use super::{ToSql};
+use super::predicate::{PredicateToSql};
+
+pub fn aggregate_to_sql(name: &str, args: &[&expression::UntypedExpression], modifiers: &AggregateModifiers, ctx: &mut super::SqlContext) -> String {
+    // Without FILTER support the predicate goes into `CASE WHEN` around the first argument,
+    // it is rendered first to keep positional placeholders in order.
+    let emulated_filter = match modifiers.filter {
+        Some(ref filter) if !ctx.adapter().supports_aggregate_filter() => Some(filter.to_sql(false, ctx)),
+        _ => None
+    };
+
+    let mut args_sql: Vec<String> = args.iter().map(|arg| arg.expression_as_sql().to_sql(ctx)).collect();
+    if let Some(filter) = emulated_filter {
+        if !args_sql.is_empty() {
+            args_sql[0] = format!("CASE WHEN {} THEN {} END", filter, args_sql[0]);
+        }
+    }
+
+    let mut sql = format!("{}{}", if modifiers.distinct { "DISTINCT " } else { "" }, args_sql.connect(", "));
+
+    if !modifiers.order_by.is_empty() {
+        if !ctx.adapter().supports_ordered_aggregates() {
+            ctx.unsupported("ORDER BY inside aggregates");
+        }
+
+        let orders: Vec<String> = modifiers.order_by.iter().map(|ord| ord.to_sql(ctx)).collect();
+        sql = format!("{} ORDER BY {}", sql, orders.connect(", "));
+    }
+
+    sql = format!("{}({})", name, sql);
+
+    match modifiers.filter {
+        Some(ref filter) if ctx.adapter().supports_aggregate_filter() => {
+            format!("{} FILTER (WHERE {})", sql, filter.to_sql(false, ctx))
+        },
+        _ => sql
+    }
+}
+
 macro_rules! agg_to_sql {
-    ($foo:ident, $foo_arg:ident, $fmt:expr) => (
+    ($foo:ident, $foo_arg:ident, $name:expr) => (
         impl<R, T, E> super::ToSql for $foo<R, T, E> where R: Clone, T: Clone, E: $foo_arg<R, T> {
             fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
-                format!($fmt, self.expression.expression_as_sql().to_sql(ctx))
+                aggregate_to_sql($name, &[&self.expression], &self.modifiers, ctx)
             }
         }
     )
 }
 
-agg_to_sql!(Sum, SumArg, "SUM({})");
-agg_to_sql!(Min, MinArg, "MIN({})");
-agg_to_sql!(Max, MaxArg, "MAX({})");
-agg_to_sql!(Avg, AvgArg, "AVG({})");
-agg_to_sql!(Count, CountArg, "COUNT({})");
+agg_to_sql!(Sum, SumArg, "SUM");
+agg_to_sql!(Min, MinArg, "MIN");
+agg_to_sql!(Max, MaxArg, "MAX");
+agg_to_sql!(Avg, AvgArg, "AVG");
+agg_to_sql!(Count, CountArg, "COUNT");
+agg_to_sql!(ArrayAgg, ArrayAggArg, "ARRAY_AGG");
+agg_to_sql!(JsonAgg, JsonAggArg, "JSON_AGG");
+
+macro_rules! bool_agg_to_sql {
+    ($foo:ident, $foo_arg:ident, $name:expr, $emulated:expr) => (
+        impl<R, T, E> super::ToSql for $foo<R, T, E> where R: Clone, T: Clone, E: $foo_arg<R, T> {
+            fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+                let name = if ctx.adapter().supports_bool_aggregates() { $name } else { $emulated };
+                aggregate_to_sql(name, &[&self.expression], &self.modifiers, ctx)
+            }
+        }
+    )
+}
+
+bool_agg_to_sql!(BoolAnd, BoolAndArg, "BOOL_AND", "MIN");
+bool_agg_to_sql!(BoolOr, BoolOrArg, "BOOL_OR", "MAX");
+
+macro_rules! double_agg_to_sql {
+    ($foo:ident, $foo_arg:ident, $name:expr) => (
+        impl<R, T, E> super::ToSql for $foo<R, T, E> where R: Clone, T: Clone, E: $foo_arg<R, T> {
+            fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+                let sql = aggregate_to_sql($name, &[&self.expression], &self.modifiers, ctx);
+                if ctx.adapter().casts_statistics_to_double() {
+                    format!("CAST({} AS DOUBLE PRECISION)", sql)
+                } else {
+                    sql
+                }
+            }
+        }
+    )
+}
+
+double_agg_to_sql!(Stddev, StddevArg, "STDDEV");
+double_agg_to_sql!(Variance, VarianceArg, "VARIANCE");
+
+impl<R, T, E> super::ToSql for StringAgg<R, T, E> where R: Clone, T: Clone, E: StringAggArg<R, T> {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        if !ctx.adapter().uses_group_concat() {
+            return aggregate_to_sql("STRING_AGG", &[&self.expression, &self.delimiter], &self.modifiers, ctx)
+        }
+
+        // GROUP_CONCAT has no FILTER but takes ORDER BY, its separator can only be a literal
+        let filter = self.modifiers.filter.as_ref().map(|filter| filter.to_sql(false, ctx));
+        let mut sql = self.expression.expression_as_sql().to_sql(ctx);
+        if let Some(filter) = filter {
+            sql = format!("CASE WHEN {} THEN {} END", filter, sql);
+        }
+
+        if self.modifiers.distinct {
+            sql = format!("DISTINCT {}", sql);
+        }
+
+        if !self.modifiers.order_by.is_empty() {
+            let orders: Vec<String> = self.modifiers.order_by.iter().map(|ord| ord.to_sql(ctx)).collect();
+            sql = format!("{} ORDER BY {}", sql, orders.connect(", "));
+        }
+
+        format!("GROUP_CONCAT({} SEPARATOR {})", sql, ctx.adapter().string_literal(&self.delimiter))
+    }
+}
+
+impl super::ToSql for JsonObjectAgg {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        aggregate_to_sql("JSON_OBJECT_AGG", &[&**self.key, &**self.value], &self.modifiers, ctx)
+    }
+}
+
+impl<R, T, E> super::ToSql for PercentileCont<R, T, E> where R: Clone, T: Clone, E: PercentileContArg<R, T> {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        if !(self.fraction >= 0.0 && self.fraction <= 1.0) {
+            ctx.error(super::SqlError::Invalid(format!("PERCENTILE_CONT fraction {} is out of 0..1", self.fraction)));
+        }
+
+        let sql = format!("PERCENTILE_CONT({}) WITHIN GROUP (ORDER BY {})", self.fraction, self.within_group.to_sql(ctx));
+        match self.filter {
+            Some(ref filter) => {
+                if !ctx.adapter().supports_aggregate_filter() {
+                    ctx.unsupported("FILTER on ordered-set aggregates");
+                }
+                format!("{} FILTER (WHERE {})", sql, filter.to_sql(false, ctx))
+            },
+            None => sql
+        }
+    }
+}
 
 impl super::ToSql for CountAll {
-    fn to_sql(&self, _ctx: &mut super::SqlContext) -> String {
-        "COUNT(*)".to_string()
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        match self.filter {
+            Some(ref filter) if ctx.adapter().supports_aggregate_filter() => {
+                format!("COUNT(*) FILTER (WHERE {})", filter.to_sql(false, ctx))
+            },
+            // NULL for rows not matching the filter, which COUNT skips
+            Some(ref filter) => format!("COUNT(CASE WHEN {} THEN 1 END)", filter.to_sql(false, ctx)),
+            None => "COUNT(*)".to_string()
+        }
+    }
+}
+
+impl super::ToSql for Grouping {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        let defs: Vec<String> = self.expressions.iter().map(|f| f.expression_as_sql().to_sql(ctx)).collect();
+        format!("GROUPING({})", defs.connect(", "))
+    }
+}
+
+impl<T> super::from::FromToSql for TableFunction<T> {
+    fn to_from_sql(&self, ctx: &mut super::SqlContext) -> String {
+        if let Some(count) = self.get_mismatched_alias() {
+            let msg = format!("{} returns {} columns, got {} column aliases", self.get_name(), self.get_columns().len(), count);
+            ctx.error(super::SqlError::Invalid(msg));
+        }
+
+        let args: Vec<String> = self.get_args().iter().map(|arg| {
+            let arg = arg.expression_as_sql().to_sql(ctx);
+            if self.is_array_args() { format!("ARRAY[{}]", arg) } else { arg }
+        }).collect();
+
+        let sql = format!("{}({})", self.get_name(), args.connect(", "));
+        match self.get_alias() {
+            &Some(ref alias) => format!("{} AS {}({})", sql, alias, self.get_columns().connect(", ")),
+            &None => sql
+        }
     }
 }
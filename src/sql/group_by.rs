@@ -1,9 +1,46 @@
 use super::super::group_by;
+use super::super::expression;
+
+fn exprs_to_sql(exprs: &Vec<expression::SharedExpression>, ctx: &mut super::SqlContext) -> String {
+    let defs: Vec<String> = exprs.iter().map(|f| f.expression_as_sql().to_sql(ctx)).collect();
+    defs.connect(", ")
+}
+
+impl super::ToSql for group_by::GroupingElement {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        match self {
+            &group_by::GroupingElement::Rollup(ref by) => format!("ROLLUP ({})", exprs_to_sql(by, ctx)),
+            &group_by::GroupingElement::Cube(ref by) => format!("CUBE ({})", exprs_to_sql(by, ctx)),
+            &group_by::GroupingElement::GroupingSets(ref sets) => {
+                let defs: Vec<String> = sets.iter().map(|set| format!("({})", exprs_to_sql(set, ctx))).collect();
+                format!("GROUPING SETS ({})", defs.connect(", "))
+            }
+        }
+    }
+}
 
 impl super::ToSql for group_by::GroupBy {
     fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
-        if !self.get_by().is_empty() {
-            let defs: Vec<String> = self.get_by().iter().map(|f| f.expression_as_sql().to_sql(ctx)).collect();
+        let mut defs: Vec<String> = self.get_by().iter().map(|f| f.expression_as_sql().to_sql(ctx)).collect();
+        let grouping = self.get_grouping();
+
+        if !grouping.is_empty() {
+            if !ctx.adapter().supports_grouping_sets() {
+                // MySQL can only roll up the whole GROUP BY list
+                if defs.is_empty() && grouping.len() == 1 && ctx.adapter().supports_with_rollup() {
+                    if let &group_by::GroupingElement::Rollup(ref by) = &grouping[0] {
+                        return format!(" GROUP BY {} WITH ROLLUP", exprs_to_sql(by, ctx))
+                    }
+                }
+
+                // Still rendered, leaving them out would group the rows differently
+                ctx.unsupported("ROLLUP, CUBE and GROUPING SETS");
+            }
+
+            defs.extend(grouping.iter().map(|g| g.to_sql(ctx)));
+        }
+
+        if !defs.is_empty() {
             format!(" GROUP BY {}", defs.connect(", "))
         } else {
             String::new()
@@ -0,0 +1,97 @@
+use super::super::index_query;
+use super::super::from::{Table};
+use super::{ToSql};
+
+impl super::ToSql for index_query::IndexMethod {
+    fn to_sql(&self, _ctx: &mut super::SqlContext) -> String {
+        match self {
+            &index_query::IndexMethod::BTree => "btree",
+            &index_query::IndexMethod::Hash => "hash",
+            &index_query::IndexMethod::Gist => "gist",
+            &index_query::IndexMethod::SpGist => "spgist",
+            &index_query::IndexMethod::Gin => "gin",
+            &index_query::IndexMethod::Brin => "brin",
+        }.to_string()
+    }
+}
+
+impl super::ToSql for index_query::CreateIndexQuery {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        // Values can't be bound in DDL, so everything is inlined as literals
+        let inline_values = ctx.is_inline_values();
+        ctx.set_inline_values(true);
+
+        let mut sql = "CREATE".to_string();
+
+        if self.is_unique() {
+            sql = format!("{} UNIQUE", sql)
+        }
+
+        sql = format!("{} INDEX", sql);
+
+        if self.is_concurrently() {
+            if !ctx.adapter().supports_index_options() {
+                ctx.unsupported("CREATE INDEX CONCURRENTLY");
+            }
+            sql = format!("{} CONCURRENTLY", sql)
+        }
+
+        if self.is_if_not_exists() {
+            sql = format!("{} IF NOT EXISTS", sql)
+        }
+
+        sql = format!("{} {} ON {}", sql, self.get_name(), self.get_table().get_table_name());
+
+        match self.get_method() {
+            &Some(ref method) => {
+                if !ctx.adapter().supports_index_options() {
+                    ctx.unsupported("Index methods");
+                }
+                sql = format!("{} USING {}", sql, method.to_sql(ctx))
+            },
+            &None => ()
+        }
+
+        if self.get_on().is_empty() {
+            ctx.error(super::SqlError::Invalid(format!("Index {} has no columns", self.get_name())));
+        }
+
+        // Plain columns are rendered unqualified, expressions need to be wrapped into parentheses
+        // and aliases have no meaning in an index
+        let on: Vec<String> = self.get_on().iter().map(|e| {
+            let e = match e.alias_of() {
+                Some((expression, _)) => expression,
+                None => e
+            };
+
+            match e.output_name() {
+                Some(name) => name,
+                None => format!("({})", e.expression_as_sql().to_sql(ctx))
+            }
+        }).collect();
+        sql = format!("{} ({})", sql, on.connect(", "));
+
+        if !self.get_include().is_empty() {
+            if !ctx.adapter().supports_index_options() {
+                ctx.unsupported("INCLUDE in indexes");
+            }
+            let include: Vec<&str> = self.get_include().iter().map(|f| f.name()).collect();
+            sql = format!("{} INCLUDE ({})", sql, include.connect(", "))
+        }
+
+        match self.get_where() {
+            &Some(ref predicate) => {
+                if !ctx.adapter().supports_partial_indexes() {
+                    ctx.unsupported("Partial indexes");
+                }
+                sql = format!("{} WHERE {}", sql, predicate.to_sql(false, ctx))
+            },
+            &None => ()
+        }
+
+        ctx.set_inline_values(inline_values);
+        sql
+    }
+}
+
+impl super::QueryToSql for index_query::CreateIndexQuery {}
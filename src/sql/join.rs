@@ -34,6 +34,10 @@ impl super::ToSql for join::Join {
             },
             &join::Join::UnconditionedJoin{ref join_type, ref from} => {
                 format!("{} {}", join_type.to_sql(ctx), from.as_sql().to_from_sql(ctx))
+            },
+            &join::Join::UsingJoin{ref join_type, ref from, ref using} => {
+                let names: Vec<&str> = using.iter().map(|f| f.name()).collect();
+                format!("{} {} USING ({})", join_type.to_sql(ctx), from.as_sql().to_from_sql(ctx), names.connect(", "))
             }
         }
     }
@@ -1,33 +1,88 @@
+use std::fmt;
+use std::error;
+
+use value::Value;
+
 pub use self::predicate::{PredicateToSql};
-pub use self::value::{ToPredicateValue};
+pub use self::value::{ToPredicateValue, ToSqlLiteral, BoxedLiteral, SharedLiteral};
 #[cfg(feature = "postgres")] pub use self::value::{AsPostgresValue};
 pub use self::from::{FromToSql};
 pub use self::adapter::{
     SqlAdapter,
     PostgreSqlAdapter,
     MysqlAdapter,
+    SqliteAdapter,
 };
 
+pub mod alias;
+pub mod alter_table;
 pub mod delete;
 pub mod distinct;
 pub mod expr_value;
 pub mod field;
 pub mod function;
 pub mod group_by;
+pub mod index;
 pub mod insert;
 pub mod join;
+pub mod modify;
 pub mod order_by;
 pub mod placeholder;
 pub mod predicate;
+pub mod schema;
 pub mod select;
+pub mod transaction;
+pub mod truncate;
 pub mod from;
 pub mod update;
 pub mod value;
 pub mod adapter;
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlError {
+    /// Query uses a feature the adapter's dialect can't express.
+    Unsupported(String),
+    /// Values don't match placeholders of a prepared template.
+    InvalidBind(String),
+    /// Query can't be rendered as valid SQL, e.g. an argument is out of range.
+    Invalid(String)
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &SqlError::Unsupported(ref msg) => write!(f, "Unsupported SQL: {}", msg),
+            &SqlError::InvalidBind(ref msg) => write!(f, "Invalid bind: {}", msg),
+            &SqlError::Invalid(ref msg) => write!(f, "Invalid SQL: {}", msg)
+        }
+    }
+}
+
+impl error::Error for SqlError {
+    fn description(&self) -> &str {
+        match self {
+            &SqlError::Unsupported(ref msg) => msg,
+            &SqlError::InvalidBind(ref msg) => msg,
+            &SqlError::Invalid(ref msg) => msg
+        }
+    }
+}
+
 pub trait QueryToSql: ToSql {
+    /// Renders the query, failing if the adapter can't express some of it.
+    fn try_to_final_sql(&self, ctx: &mut SqlContext) -> Result<String, SqlError> {
+        ctx.errors.clear();
+        let sql = self.to_final_sql(ctx);
+        match ctx.get_errors().first() {
+            Some(err) => Err(err.clone()),
+            None => Ok(sql)
+        }
+    }
+
+    /// Renders the query as is, even with parts the adapter can't express (see `try_to_final_sql`).
     fn to_final_sql(&self, ctx: &mut SqlContext) -> String {
         let mut sql = format!("{};", self.to_sql(ctx));
+
         let mut idx = ctx.get_expl_placeholders_count() + 1;
         for i in 0..ctx.get_impl_placeholders_count() {
             sql = sql.replace(&format!("$${}", i), &ctx.adapter().placeholder(idx));
@@ -36,6 +91,35 @@ pub trait QueryToSql: ToSql {
 
         sql
     }
+
+    /// Renders the query as separate statements, for drivers that run one statement per call.
+    fn try_to_final_statements(&self, ctx: &mut SqlContext) -> Result<Vec<String>, SqlError> {
+        self.try_to_final_sql(ctx).map(|sql| vec![sql])
+    }
+}
+
+/// Commands that some dialects can only express with several statements.
+pub trait StatementsToSql {
+    fn to_statements(&self, ctx: &mut SqlContext) -> Vec<String>;
+}
+
+/// `to_sql` of a `StatementsToSql` command, fails if it doesn't fit in a single statement.
+pub fn statements_to_sql(query: &StatementsToSql, ctx: &mut SqlContext) -> String {
+    let statements = query.to_statements(ctx);
+    if statements.len() > 1 {
+        ctx.error(SqlError::Invalid(format!("command renders {} statements, use try_to_final_statements", statements.len())));
+    }
+    statements.connect("; ")
+}
+
+/// `try_to_final_statements` of a `StatementsToSql` command.
+pub fn statements_to_final_sql(query: &StatementsToSql, ctx: &mut SqlContext) -> Result<Vec<String>, SqlError> {
+    ctx.errors.clear();
+    let statements: Vec<String> = query.to_statements(ctx).into_iter().map(|sql| format!("{};", sql)).collect();
+    match ctx.get_errors().first() {
+        Some(err) => Err(err.clone()),
+        None => Ok(statements)
+    }
 }
 
 pub trait ToSql {
@@ -53,7 +137,12 @@ pub struct SqlContext {
     impl_placeholders: u8,
     expl_placeholders: u8,
     placeholder_data: Vec<BoxedValue>,
+    values: Vec<Value>,
+    placeholder_types: Vec<Option<&'static str>>,
     adapter: Box<SqlAdapter + 'static>,
+    errors: Vec<SqlError>,
+    inline_values: bool,
+    opaque_values: usize,
 }
 
 #[allow(dead_code)]
@@ -63,17 +152,36 @@ impl SqlContext {
             impl_placeholders: 0u8,
             expl_placeholders: 0u8,
             placeholder_data: vec![],
+            values: vec![],
+            placeholder_types: vec![],
             adapter: adapter,
+            errors: vec![],
+            inline_values: false,
+            opaque_values: 0,
         }
     }
 
-    pub fn hold(&mut self, val: BoxedValue) -> String {
+    /// Holds a value for an implicit placeholder, `value` is its driver independent form.
+    pub fn hold_value(&mut self, val: BoxedValue, value: Value) -> String {
         self.placeholder_data.push(val);
+        self.values.push(value);
         let res = format!("$${}", self.impl_placeholders);
         self.impl_placeholders += 1;
         res
     }
 
+    /// Holds a value known only to the PostgreSQL driver, `values()` reports it as NULL.
+    #[deprecated(since = "0.4.0", note = "use `hold_value`, backends other than PostgreSQL can't bind opaque values")]
+    pub fn hold(&mut self, val: BoxedValue) -> String {
+        self.opaque_values += 1;
+        self.hold_value(val, Value::Null)
+    }
+
+    /// Whether some values were held with `hold` and are missing from `values()`.
+    pub fn has_opaque_values(&self) -> bool {
+        self.opaque_values > 0
+    }
+
     pub fn get_impl_placeholders_count(&self) -> u8 {
         self.impl_placeholders
     }
@@ -90,7 +198,71 @@ impl SqlContext {
         if idx > self.expl_placeholders { self.expl_placeholders = idx; }
     }
 
+    /// Binds a value without rendering, e.g. for a `PreparedTemplate`.
+    pub fn bind(&mut self, value: Value) {
+        self.bind_as(value, None)
+    }
+
+    /// Binds a value to a placeholder of `value_type`, so NULL gets the placeholder's type.
+    pub fn bind_as(&mut self, value: Value, value_type: Option<&str>) {
+        self.placeholder_data.push(value.to_boxed_as(value_type));
+        self.values.push(value);
+    }
+
+    pub fn placeholder_type(&mut self, idx: u8, value_type: &'static str) {
+        let idx = idx as usize;
+        if self.placeholder_types.len() < idx {
+            self.placeholder_types.resize(idx, None);
+        }
+        self.placeholder_types[idx - 1] = Some(value_type);
+    }
+
+    /// Value types of explicit placeholders, `None` for untyped ones.
+    pub fn get_placeholder_types(&self) -> Vec<Option<&'static str>> {
+        (1..self.expl_placeholders as usize + 1).map(|idx| {
+            self.placeholder_types.get(idx - 1).and_then(|t| *t)
+        }).collect()
+    }
+
     pub fn data(&self) -> &[BoxedValue] {
         &self.placeholder_data
     }
+
+    /// Held values in a driver independent form, in the same order as `data()`.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn error(&mut self, err: SqlError) {
+        self.errors.push(err);
+    }
+
+    pub fn unsupported(&mut self, feature: &str) {
+        let msg = format!("{} is not supported by {}", feature, self.adapter.name());
+        self.error(SqlError::Unsupported(msg));
+    }
+
+    pub fn get_errors(&self) -> &[SqlError] {
+        &self.errors
+    }
+
+    /// Forgets placeholders, held values and errors of previously rendered queries, keeps the adapter.
+    pub fn reset(&mut self) {
+        self.impl_placeholders = 0;
+        self.expl_placeholders = 0;
+        self.placeholder_data.clear();
+        self.values.clear();
+        self.placeholder_types.clear();
+        self.errors.clear();
+        self.opaque_values = 0;
+    }
+
+    /// Render values as SQL literals instead of placeholders (for statements that can't be parametrized).
+    pub fn set_inline_values(&mut self, inline: bool) {
+        self.inline_values = inline;
+    }
+
+    pub fn is_inline_values(&self) -> bool {
+        self.inline_values
+    }
 }
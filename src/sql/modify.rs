@@ -0,0 +1,99 @@
+use super::super::from;
+use super::super::order_by;
+use super::super::predicate;
+use super::from::{FromToSql};
+use super::predicate::{PredicateToSql};
+use super::{ToSql};
+
+/// Additional tables of the statement, `FROM` for UPDATE and `USING` for DELETE.
+pub fn sources_to_sql(keyword: &str, sources: &Option<Vec<from::SharedFrom>>, ctx: &mut super::SqlContext) -> String {
+    match sources.as_ref() {
+        Some(sources) if !sources.is_empty() => {
+            let tables_str: Vec<String> = sources.iter().map(|v| v.as_sql().to_from_sql(ctx)).collect();
+            format!(" {} {}", keyword, tables_str.connect(", "))
+        },
+        _ => String::new()
+    }
+}
+
+pub fn where_to_sql(where_: &Option<predicate::SharedPredicate>, all: bool, ctx: &mut super::SqlContext) -> String {
+    match where_.as_ref() {
+        Some(predicate) => format!(" WHERE {}", predicate.to_sql(false, ctx)),
+        // http://devopsreactions.tumblr.com/post/47352638154/almost-ran-update-without-where
+        None if !all => " WHERE true = false".to_string(),
+        _ => String::new()
+    }
+}
+
+pub fn order_limit_to_sql(sources: &Option<Vec<from::SharedFrom>>, order_by: &Vec<order_by::OrderBy>, limit: &Option<usize>,
+                          ctx: &mut super::SqlContext) -> String {
+    let mut sql = String::new();
+
+    let joined = sources.as_ref().map_or(false, |sources| !sources.is_empty());
+    if joined && (!order_by.is_empty() || limit.is_some()) {
+        ctx.unsupported("ORDER BY and LIMIT in multi-table UPDATE and DELETE");
+    }
+
+    if !order_by.is_empty() {
+        let orders: Vec<String> = order_by.iter().map(|ord| ord.to_sql(ctx)).collect();
+        sql = format!(" ORDER BY {}", orders.connect(", "))
+    }
+
+    if limit.is_some() {
+        sql = format!("{} LIMIT {}", sql, limit.unwrap())
+    }
+
+    sql
+}
+
+/// Emulates ORDER BY and LIMIT of UPDATE and DELETE on adapters that only have a row identifier:
+/// `WHERE ctid IN (SELECT ctid FROM ... ORDER BY ... LIMIT n)`.
+///
+/// Additional tables stay in the outer statement (SET and RETURNING may refer to them), their row
+/// identifiers are chosen by the subquery too, so WHERE is only rendered there.
+pub fn emulated_order_limit_to_sql(
+    keyword: &str,
+    table: &from::SharedTable,
+    sources: &Option<Vec<from::SharedFrom>>,
+    where_: &Option<predicate::SharedPredicate>,
+    all: bool,
+    order_by: &Vec<order_by::OrderBy>,
+    limit: &Option<usize>,
+    ctx: &mut super::SqlContext) -> String {
+
+    let row_id = match ctx.adapter().row_identifier() {
+        Some(row_id) => row_id,
+        None => {
+            ctx.unsupported("ORDER BY and LIMIT in UPDATE and DELETE");
+            return String::new()
+        }
+    };
+
+    let qual = table.get_table_alias().as_ref().unwrap_or(table.get_table_name()).to_string();
+
+    let mut row_ids = vec![format!("{}.{}", qual, row_id)];
+    let sources: &[from::SharedFrom] = sources.as_ref().map_or(&[], |sources| &sources[..]);
+    for source in sources.iter() {
+        match source.as_table() {
+            Some(table) => {
+                let qual = table.get_table_alias().as_ref().unwrap_or(table.get_table_name());
+                row_ids.push(format!("{}.{}", qual, row_id))
+            },
+            None => ctx.unsupported("ORDER BY and LIMIT in UPDATE and DELETE joined to subqueries")
+        }
+    }
+
+    let sources_str: Vec<String> = sources.iter().map(|v| v.as_sql().to_from_sql(ctx)).collect();
+
+    let mut sql = String::new();
+    let mut select = format!("SELECT {} FROM {}", row_ids.connect(", "), table.to_from_sql(ctx));
+    if !sources_str.is_empty() {
+        sql = format!(" {} {}", keyword, sources_str.connect(", "));
+        select = format!("{}, {}", select, sources_str.connect(", "))
+    }
+
+    select = format!("{}{}{}", select, where_to_sql(where_, all, ctx), order_limit_to_sql(&None, order_by, limit, ctx));
+
+    let row_ids = if row_ids.len() > 1 { format!("({})", row_ids.connect(", ")) } else { row_ids.connect(", ") };
+    format!("{} WHERE {} IN ({})", sql, row_ids, select)
+}
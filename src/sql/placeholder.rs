@@ -1,11 +1,26 @@
 use super::super::placeholder;
+use super::super::visitor::{Visitor, Rewriter};
 use super::value::{self, ToPredicateValue};
 
 impl value::ToPredicateValue for placeholder::Placeholder {
     fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String {
+        // Values are inlined where nothing can be bound, e.g. in DDL
+        if ctx.is_inline_values() {
+            ctx.error(super::SqlError::Invalid(format!("placeholder ${} in a statement that can't bind values", self.get_idx())));
+        }
+
         ctx.expl_indexed_placeholder(self.get_idx());
+        if let Some(value_type) = self.get_value_type() {
+            ctx.placeholder_type(self.get_idx(), value_type);
+        }
         ctx.adapter().placeholder(self.get_idx())
     }
+
+    fn accept_value(&self, visitor: &mut Visitor) {
+        visitor.visit_placeholder(self)
+    }
+
+    fn rewrite_value(&mut self, _rewriter: &mut Rewriter) {}
 }
 
 impl super::ToSql for placeholder::Placeholder {
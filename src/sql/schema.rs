@@ -0,0 +1,74 @@
+use super::super::schema;
+use super::super::from::{Table};
+
+// `keyed` is set for columns of table-level keys, column-level keys and defaults are checked here
+pub fn column_def_to_sql(column: &schema::ColumnDef, keyed: bool, ctx: &mut super::SqlContext) -> String {
+    let keyed = keyed || column.is_primary_key() || column.is_unique() ||
+        column.get_references().is_some() || column.get_default().is_some();
+
+    let column_type = ctx.adapter().column_type(column.get_column_type());
+    if keyed && !ctx.adapter().supports_keyed_column(column.get_column_type()) {
+        ctx.unsupported(&format!("{} in keys and with defaults", column_type));
+    }
+
+    let mut sql = format!("{} {}", column.get_name(), column_type);
+
+    if !column.is_nullable() && !column.is_primary_key() {
+        sql = format!("{} NOT NULL", sql)
+    }
+
+    match column.get_default() {
+        &Some(ref default) => sql = format!("{} DEFAULT {}", sql, default.to_sql_literal(ctx)),
+        &None => ()
+    }
+
+    if column.is_primary_key() {
+        sql = format!("{} PRIMARY KEY", sql)
+    }
+
+    if column.is_unique() {
+        sql = format!("{} UNIQUE", sql)
+    }
+
+    match column.get_references() {
+        &Some((ref table, ref column)) => sql = format!("{} REFERENCES {} ({})", sql, table, column),
+        &None => ()
+    }
+
+    sql
+}
+
+impl super::ToSql for schema::ColumnDef {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        column_def_to_sql(self, false, ctx)
+    }
+}
+
+impl super::ToSql for schema::CreateTableQuery {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        let schema = self.get_schema();
+        let mut sql = "CREATE TABLE".to_string();
+
+        if self.is_if_not_exists() {
+            sql = format!("{} IF NOT EXISTS", sql)
+        }
+
+        let mut defs: Vec<String> = schema.get_columns().iter().map(|c| {
+            let keyed = schema.get_primary_key().contains(c.get_name()) ||
+                schema.get_unique().iter().any(|unique| unique.contains(c.get_name()));
+            column_def_to_sql(c, keyed, ctx)
+        }).collect();
+
+        if !schema.get_primary_key().is_empty() {
+            defs.push(format!("PRIMARY KEY ({})", schema.get_primary_key().connect(", ")))
+        }
+
+        for unique in schema.get_unique().iter() {
+            defs.push(format!("UNIQUE ({})", unique.connect(", ")))
+        }
+
+        format!("{} {} ({})", sql, schema.get_table().get_table_name(), defs.connect(", "))
+    }
+}
+
+impl super::QueryToSql for schema::CreateTableQuery {}
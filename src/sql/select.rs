@@ -1,5 +1,6 @@
-use super::super::from;
+use super::super::from::{self, Table};
 use super::super::select_query;
+use super::super::visitor::{Visitor, Visitable, Rewriter, Rewritable};
 use super::{ToSql};
 
 impl<T, L, M> super::from::FromToSql for from::FromSelect<T, L, M> {
@@ -9,13 +10,36 @@ impl<T, L, M> super::from::FromToSql for from::FromSelect<T, L, M> {
 }
 
 impl super::ToSql for select_query::SelectFor {
-    fn to_sql(&self, _ctx: &mut super::SqlContext) -> String {
-        match self {
-            &select_query::SelectFor::Update => "FOR UPDATE",
-            &select_query::SelectFor::UpdateNoWait => "FOR UPDATE NOWAIT",
-            &select_query::SelectFor::Share => "FOR SHARE",
-            &select_query::SelectFor::ShareNoWait => "FOR SHARE NOWAIT",
-        }.to_string()
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        let strength = match self.get_strength() {
+            &select_query::LockStrength::Update => "FOR UPDATE",
+            &select_query::LockStrength::NoKeyUpdate => "FOR NO KEY UPDATE",
+            &select_query::LockStrength::Share => "FOR SHARE",
+            &select_query::LockStrength::KeyShare => "FOR KEY SHARE",
+        };
+
+        match self.get_strength() {
+            _ if !ctx.adapter().supports_row_locks() => ctx.unsupported(strength),
+            &select_query::LockStrength::NoKeyUpdate | &select_query::LockStrength::KeyShare if !ctx.adapter().supports_key_locks() => {
+                ctx.unsupported(strength)
+            },
+            _ => ()
+        }
+
+        let mut sql = strength.to_string();
+
+        if !self.get_of().is_empty() {
+            let tables: Vec<String> = self.get_of().iter().map(|t| {
+                t.get_table_alias().as_ref().unwrap_or(t.get_table_name()).to_string()
+            }).collect();
+            sql = format!("{} OF {}", sql, tables.connect(", "))
+        }
+
+        match self.get_wait() {
+            &select_query::LockWait::Wait => sql,
+            &select_query::LockWait::NoWait => format!("{} NOWAIT", sql),
+            &select_query::LockWait::SkipLocked => format!("{} SKIP LOCKED", sql),
+        }
     }
 }
 
@@ -63,8 +87,9 @@ impl<T, L, M> super::ToSql for select_query::SelectQuery<T, L, M> {
             sql = format!("{} OFFSET {}", sql, self.get_offset().unwrap())
         }
 
-        if self.get_for().is_some() {
-            sql = format!("{} {}", sql, self.get_for().as_ref().unwrap().to_sql(ctx))
+        if !self.get_for().is_empty() {
+            let locks: Vec<String> = self.get_for().iter().map(|lock| lock.to_sql(ctx)).collect();
+            sql = format!("{} {}", sql, locks.connect(" "))
         }
 
         sql
@@ -83,7 +108,10 @@ impl super::ToSql for select_query::Select {
     fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
         match self {
             &select_query::Select::Only(ref fields) => {
-                let defs: Vec<String> = fields.iter().map(|f| f.expression_as_sql().to_sql(ctx)).collect();
+                let defs: Vec<String> = fields.iter().map(|f| match f.alias_of() {
+                    Some((expression, alias)) => format!("{} AS {}", expression.expression_as_sql().to_sql(ctx), alias),
+                    None => f.expression_as_sql().to_sql(ctx)
+                }).collect();
                 defs.connect(", ")
             },
             &select_query::Select::All => "*".to_string()
@@ -91,6 +119,8 @@ impl super::ToSql for select_query::Select {
     }
 }
 
-impl<T, L, M> super::ToPredicateValue for select_query::SelectQuery<T, L, M> {
+impl<T: Clone, L: Clone, M: Clone> super::ToPredicateValue for select_query::SelectQuery<T, L, M> {
     fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String { self.to_sql(ctx) }
+    fn accept_value(&self, visitor: &mut Visitor) { self.accept(visitor) }
+    fn rewrite_value(&mut self, rewriter: &mut Rewriter) { self.rewrite(rewriter) }
 }
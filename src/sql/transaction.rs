@@ -0,0 +1,72 @@
+use super::super::transaction;
+use super::{ToSql};
+
+impl super::ToSql for transaction::IsolationLevel {
+    fn to_sql(&self, _ctx: &mut super::SqlContext) -> String {
+        match self {
+            &transaction::IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            &transaction::IsolationLevel::ReadCommitted => "READ COMMITTED",
+            &transaction::IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            &transaction::IsolationLevel::Serializable => "SERIALIZABLE",
+        }.to_string()
+    }
+}
+
+impl super::StatementsToSql for transaction::TransactionOptions {
+    fn to_statements(&self, ctx: &mut super::SqlContext) -> Vec<String> {
+        let has_modes = self.get_isolation().is_some() || self.is_read_only() || self.is_deferrable();
+        if has_modes && !ctx.adapter().supports_transaction_modes() {
+            ctx.unsupported("Transaction modes");
+        }
+
+        if self.is_deferrable() && !ctx.adapter().supports_deferrable_transactions() {
+            ctx.unsupported("DEFERRABLE transactions");
+        }
+
+        let access = if self.is_read_only() { Some("READ ONLY".to_string()) } else { None };
+
+        match self.get_isolation() {
+            &Some(ref isolation) if ctx.adapter().sets_transaction_isolation() => {
+                // Isolation level can't be set in START TRANSACTION, it applies to the next one
+                let start = match access {
+                    Some(access) => format!("START TRANSACTION {}", access),
+                    None => "START TRANSACTION".to_string()
+                };
+                vec![format!("SET TRANSACTION ISOLATION LEVEL {}", isolation.to_sql(ctx)), start]
+            },
+            isolation => {
+                let mut modes = vec![];
+
+                if let &Some(ref isolation) = isolation {
+                    modes.push(format!("ISOLATION LEVEL {}", isolation.to_sql(ctx)));
+                }
+
+                if let Some(access) = access {
+                    modes.push(access);
+                }
+
+                if self.is_deferrable() {
+                    modes.push("DEFERRABLE".to_string());
+                }
+
+                if modes.is_empty() {
+                    vec!["BEGIN".to_string()]
+                } else {
+                    vec![format!("BEGIN {}", modes.connect(", "))]
+                }
+            }
+        }
+    }
+}
+
+impl super::ToSql for transaction::TransactionOptions {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        super::statements_to_sql(self, ctx)
+    }
+}
+
+impl super::QueryToSql for transaction::TransactionOptions {
+    fn try_to_final_statements(&self, ctx: &mut super::SqlContext) -> Result<Vec<String>, super::SqlError> {
+        super::statements_to_final_sql(self, ctx)
+    }
+}
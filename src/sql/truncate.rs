@@ -0,0 +1,67 @@
+use super::super::truncate_query;
+use super::super::from::{Table};
+
+impl super::StatementsToSql for truncate_query::TruncateQuery {
+    fn to_statements(&self, ctx: &mut super::SqlContext) -> Vec<String> {
+        let tables: Vec<String> = self.get_tables().iter().map(|t| t.get_table_name().to_string()).collect();
+
+        if !ctx.adapter().supports_truncate() {
+            // Plain DELETE without WHERE is the closest thing, identity and cascading can't be expressed
+            if self.get_identity() == &Some(truncate_query::TruncateIdentity::Restart) {
+                ctx.unsupported("TRUNCATE ... RESTART IDENTITY");
+            }
+
+            if self.get_behavior() == &Some(truncate_query::TruncateBehavior::Cascade) {
+                ctx.unsupported("TRUNCATE ... CASCADE");
+            }
+
+            return tables.iter().map(|t| format!("DELETE FROM {}", t)).collect()
+        }
+
+        if self.is_only() && !ctx.adapter().supports_truncate_only() {
+            ctx.unsupported("TRUNCATE ONLY");
+        }
+
+        if self.get_identity().is_some() && !ctx.adapter().supports_truncate_identity() {
+            ctx.unsupported("TRUNCATE ... RESTART IDENTITY and CONTINUE IDENTITY");
+        }
+
+        if self.get_behavior().is_some() && !ctx.adapter().supports_truncate_cascade() {
+            ctx.unsupported("TRUNCATE ... CASCADE and RESTRICT");
+        }
+
+        let mut options = String::new();
+
+        match self.get_identity() {
+            &Some(truncate_query::TruncateIdentity::Restart) => options.push_str(" RESTART IDENTITY"),
+            &Some(truncate_query::TruncateIdentity::Continue) => options.push_str(" CONTINUE IDENTITY"),
+            &None => ()
+        }
+
+        match self.get_behavior() {
+            &Some(truncate_query::TruncateBehavior::Cascade) => options.push_str(" CASCADE"),
+            &Some(truncate_query::TruncateBehavior::Restrict) => options.push_str(" RESTRICT"),
+            &None => ()
+        }
+
+        let only = if self.is_only() { "ONLY " } else { "" };
+
+        if !ctx.adapter().supports_truncate_tables() {
+            return tables.iter().map(|t| format!("TRUNCATE {}{}{}", only, t, options)).collect()
+        }
+
+        vec![format!("TRUNCATE {}{}{}", only, tables.connect(", "), options)]
+    }
+}
+
+impl super::ToSql for truncate_query::TruncateQuery {
+    fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
+        super::statements_to_sql(self, ctx)
+    }
+}
+
+impl super::QueryToSql for truncate_query::TruncateQuery {
+    fn try_to_final_statements(&self, ctx: &mut super::SqlContext) -> Result<Vec<String>, super::SqlError> {
+        super::statements_to_final_sql(self, ctx)
+    }
+}
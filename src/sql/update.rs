@@ -1,6 +1,7 @@
 use super::super::update_query;
 use super::from::{FromToSql};
 use super::value;
+use super::modify;
 
 impl<F: value::ToPredicateValue, T: value::ToPredicateValue> super::ToSql for update_query::FieldUpdate<F, T> {
     fn to_sql(&self, ctx: &mut super::SqlContext) -> String {
@@ -21,23 +22,16 @@ impl<T, L, M> super::ToSql for update_query::UpdateQuery<T, L, M> {
         let updates_str: Vec<String> = self.get_updates().iter().map(|upd| upd.to_sql(ctx)).collect();
         sql = format!("{} SET {}", sql, updates_str.connect(", "));
 
-        if self.get_from().is_some() {
-            let from = self.get_from().as_ref().unwrap();
-            if !from.is_empty() {
-                let tables_str: Vec<String> = from.iter().map(|v| v.as_sql().to_from_sql(ctx)).collect();
-                sql = format!("{} FROM {}", sql, tables_str.connect(", "))
-            }
-        }
-
-        match self.get_where().as_ref() {
-            Some(predicate) => {
-                sql = format!("{} WHERE {}", sql, predicate.to_sql(false, ctx))
-            },
-            None if !self.is_all() => {
-                // http://devopsreactions.tumblr.com/post/47352638154/almost-ran-update-without-where
-                sql = format!("{} WHERE true = false", sql)
-            },
-            _ => ()
+        let ordered = !self.get_order_by().is_empty() || self.get_limit().is_some();
+        if ordered && !ctx.adapter().supports_order_limit_in_modify() {
+            sql = format!("{}{}", sql, modify::emulated_order_limit_to_sql(
+                "FROM", self.get_table(), self.get_from(), self.get_where(), self.is_all(),
+                self.get_order_by(), self.get_limit(), ctx
+            ));
+        } else {
+            sql = format!("{}{}", sql, modify::sources_to_sql("FROM", self.get_from(), ctx));
+            sql = format!("{}{}", sql, modify::where_to_sql(self.get_where(), self.is_all(), ctx));
+            sql = format!("{}{}", sql, modify::order_limit_to_sql(self.get_from(), self.get_order_by(), self.get_limit(), ctx));
         }
 
         match self.get_returning() {
@@ -1,3 +1,4 @@
+use std::rc;
 use serialize::json;
 use time;
 use uuid;
@@ -5,11 +6,113 @@ use uuid;
 
 use expression;
 use field;
+use value::{ToValue, Value};
+use visitor::{Visitor, Rewriter};
 
 use super::{ToSql};
 
 pub trait ToPredicateValue {
     fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String;
+
+    /// Walks fields and values this operand consists of.
+    fn accept_value(&self, visitor: &mut Visitor);
+
+    /// Rewrites subqueries of this operand.
+    fn rewrite_value(&mut self, rewriter: &mut Rewriter);
+}
+
+/// Values that can be inlined into SQL text where placeholders are not allowed (e.g. DDL).
+pub trait ToSqlLiteral {
+    /// Literal in the dialect of the adapter, quoting differs between databases.
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String;
+    fn upcast_literal(&self) -> SharedLiteral;
+}
+
+pub type BoxedLiteral = Box<ToSqlLiteral + 'static>;
+pub type SharedLiteral = rc::Rc<BoxedLiteral>;
+
+macro_rules! upcast_literal {
+    () => (
+        fn upcast_literal(&self) -> SharedLiteral {
+            rc::Rc::new(Box::new(self.clone()))
+        }
+    )
+}
+
+macro_rules! number_to_sql_literal {
+    ($t:ty) => (
+        impl ToSqlLiteral for $t {
+            fn to_sql_literal(&self, _ctx: &mut super::SqlContext) -> String { self.to_string() }
+            upcast_literal!();
+        }
+    )
+}
+
+number_to_sql_literal!(i8);
+number_to_sql_literal!(i16);
+number_to_sql_literal!(i32);
+number_to_sql_literal!(i64);
+
+macro_rules! float_to_sql_literal {
+    ($t:ty, $double:expr) => (
+        impl ToSqlLiteral for $t {
+            fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String {
+                if self.is_finite() {
+                    return self.to_string()
+                }
+
+                // NaN and infinities are no numeric literals
+                match ctx.adapter().special_float_literal(*self as f64, $double) {
+                    Some(literal) => literal,
+                    None => {
+                        let msg = format!("{} has no literal for {}", ctx.adapter().name(), self);
+                        ctx.error(super::SqlError::Invalid(msg));
+                        self.to_string()
+                    }
+                }
+            }
+
+            upcast_literal!();
+        }
+    )
+}
+
+float_to_sql_literal!(f32, false);
+float_to_sql_literal!(f64, true);
+
+impl ToSqlLiteral for bool {
+    fn to_sql_literal(&self, _ctx: &mut super::SqlContext) -> String { if *self { "true" } else { "false" }.to_string() }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for String {
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String { ctx.adapter().string_literal(self) }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for Vec<u8> {
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String { ctx.adapter().bytes_literal(self) }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for json::Json {
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String { self.to_string().to_sql_literal(ctx) }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for time::Timespec {
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String { time::at_utc(*self).rfc3339().to_string().to_sql_literal(ctx) }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for uuid::Uuid {
+    fn to_sql_literal(&self, ctx: &mut super::SqlContext) -> String { self.to_string().to_sql_literal(ctx) }
+    upcast_literal!();
+}
+
+impl ToSqlLiteral for expression::RawExpression {
+    fn to_sql_literal(&self, _ctx: &mut super::SqlContext) -> String { self.content.to_string() }
+    upcast_literal!();
 }
 
 // Trait to connect Deuterium and rust-postgres
@@ -24,12 +127,16 @@ macro_rules! to_predicate_for_field {
     ($f:ty) => (
         impl ToPredicateValue for $f  {
             fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String { self.to_sql(ctx) }
+            fn accept_value(&self, visitor: &mut Visitor) { visitor.visit_field(self) }
+            fn rewrite_value(&mut self, _rewriter: &mut Rewriter) {}
         }
     )
 }
 
-impl<T: Clone> ToPredicateValue for field::NamedField<T> {
+impl<T: Clone + 'static> ToPredicateValue for field::NamedField<T> {
     fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String { self.to_sql(ctx) }
+    fn accept_value(&self, visitor: &mut Visitor) { visitor.visit_field(self) }
+    fn rewrite_value(&mut self, _rewriter: &mut Rewriter) {}
 }
 
 macro_rules! raw_value_to_predicate_value {
@@ -39,8 +146,18 @@ macro_rules! raw_value_to_predicate_value {
 
         impl ToPredicateValue for $t {
             fn to_predicate_value(&self, ctx: &mut super::SqlContext) -> String {
-                ctx.hold(Box::new(self.clone()))
+                if ctx.is_inline_values() {
+                    self.to_sql_literal(ctx)
+                } else {
+                    ctx.hold_value(Box::new(self.clone()), self.to_value())
+                }
+            }
+
+            fn accept_value(&self, visitor: &mut Visitor) {
+                visitor.visit_value(&self.to_value())
             }
+
+            fn rewrite_value(&mut self, _rewriter: &mut Rewriter) {}
         }
     )
 }
@@ -62,6 +179,12 @@ impl ToPredicateValue for expression::RawExpression {
     fn to_predicate_value(&self, _ctx: &mut super::SqlContext) -> String {
         self.content.to_string()
     }
+
+    fn accept_value(&self, visitor: &mut Visitor) {
+        visitor.visit_raw(&self.content)
+    }
+
+    fn rewrite_value(&mut self, _rewriter: &mut Rewriter) {}
 }
 
 macro_rules! extended_impl {
@@ -76,6 +199,19 @@ macro_rules! extended_impl {
                     &None => "NULL".to_string()
                 }
             }
+
+            fn accept_value(&self, visitor: &mut Visitor) {
+                match self {
+                    &Some(ref value) => value.accept_value(visitor),
+                    &None => visitor.visit_value(&Value::Null)
+                }
+            }
+
+            fn rewrite_value(&mut self, rewriter: &mut Rewriter) {
+                if let &mut Some(ref mut value) = self {
+                    value.rewrite_value(rewriter)
+                }
+            }
         }
     )
 }
@@ -101,6 +237,18 @@ impl<T: ToPredicateValue> ToPredicateValue for Vec<T> {
         let values: Vec<String> = self.iter().map(|v| v.to_predicate_value(ctx)).collect();
         values.connect(", ")
     }
+
+    fn accept_value(&self, visitor: &mut Visitor) {
+        for value in self.iter() {
+            value.accept_value(visitor)
+        }
+    }
+
+    fn rewrite_value(&mut self, rewriter: &mut Rewriter) {
+        for value in self.iter_mut() {
+            value.rewrite_value(rewriter)
+        }
+    }
 }
 
 impl<T: ToPredicateValue> super::ToSql for Vec<T> {
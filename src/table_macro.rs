@@ -0,0 +1,97 @@
+/// Declares tables with typed fields, so column names are written once.
+///
+/// ```ignore
+/// table! {
+///     jedi {
+///         id: i32,
+///         name: String,
+///         master_id: Option<i32>
+///     }
+/// }
+///
+/// let query = jedi::table().select_1(&jedi::name()).where_(jedi::id().is(1));
+///
+/// let j = jedi::alias("j");
+/// let query = j.table().select_1(&j.name());
+/// ```
+///
+/// Every table becomes a module with `table()`, a field constructor per column,
+/// `alias(name)` returning an `Alias` with fields qualified by the alias,
+/// `NAME`, `COLUMNS` and `schema()` with a column definition per field.
+///
+/// Field constructors live in the `columns` submodule and are re-exported next to the table
+/// helpers. A column named like a helper (e.g. `table` or `schema`) is shadowed by it and
+/// stays available as `jedi::columns::table()`, and as `j.columns().table()` on an alias.
+#[macro_export]
+macro_rules! table {
+    ($($table:ident { $($field:ident : $t:ty),+ $(,)* })+) => (
+        $(
+            #[allow(dead_code)]
+            pub mod $table {
+                pub use self::columns::*;
+
+                pub const NAME: &'static str = stringify!($table);
+                pub const COLUMNS: &'static [&'static str] = &[$(stringify!($field)),+];
+
+                pub fn table() -> $crate::TableDef {
+                    $crate::TableDef::new(NAME)
+                }
+
+                pub fn schema() -> $crate::TableSchema {
+                    table().schema()
+                        $(.column($crate::ColumnDef::of(&columns::$field())))+
+                }
+
+                pub fn alias(alias: &str) -> Alias {
+                    Alias { table: table().alias(alias), columns: AliasColumns { table: table().alias(alias) } }
+                }
+
+                pub mod columns {
+                    $(
+                        pub fn $field() -> $crate::NamedField<$t> {
+                            $crate::NamedField::<$t>::field_of(stringify!($field), &super::table())
+                        }
+                    )+
+                }
+
+                #[derive(Clone)]
+                pub struct Alias {
+                    table: $crate::TableDef,
+                    columns: AliasColumns
+                }
+
+                impl Alias {
+                    pub fn table(&self) -> &$crate::TableDef {
+                        &self.table
+                    }
+
+                    pub fn columns(&self) -> &AliasColumns {
+                        &self.columns
+                    }
+                }
+
+                // Field methods are reached through deref, so they never clash with `table()`
+                impl ::std::ops::Deref for Alias {
+                    type Target = AliasColumns;
+
+                    fn deref(&self) -> &AliasColumns {
+                        &self.columns
+                    }
+                }
+
+                #[derive(Clone)]
+                pub struct AliasColumns {
+                    table: $crate::TableDef
+                }
+
+                impl AliasColumns {
+                    $(
+                        pub fn $field(&self) -> $crate::NamedField<$t> {
+                            $crate::NamedField::<$t>::field_of(stringify!($field), &self.table)
+                        }
+                    )+
+                }
+            }
+        )+
+    )
+}
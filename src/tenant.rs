@@ -0,0 +1,574 @@
+use std::fmt;
+use std::error;
+
+use super::from::{Table};
+use super::field::{self, Field};
+use super::join;
+use super::expression::{ToExpression, RawExpression};
+use super::insert_query::{self, InsertValue, InsertRow};
+use super::predicate::{self, ToIsPredicate, ToAndPredicate};
+use super::placeholder;
+use super::select_query::{self, Queryable};
+use super::update_query;
+use super::delete_query;
+use super::sql;
+use super::visitor::{Visitor, Visitable, PredicateKind, Rewriter, Rewritable, RewritableSelect};
+use super::value::{ToValue, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TenantError {
+    /// Query compares or sets the tenant column of the table to another tenant.
+    ConflictingTenant(String),
+    /// Scoped table can't be filtered in this query, e.g. outer join without ON.
+    Unscopable(String)
+}
+
+impl fmt::Display for TenantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &TenantError::ConflictingTenant(ref table) => write!(f, "Query uses another tenant of table {}", table),
+            &TenantError::Unscopable(ref table) => write!(f, "Table {} can't be scoped to the tenant", table)
+        }
+    }
+}
+
+impl error::Error for TenantError {
+    fn description(&self) -> &str {
+        match self {
+            &TenantError::ConflictingTenant(_) => "Query uses another tenant",
+            &TenantError::Unscopable(_) => "Table can't be scoped to the tenant"
+        }
+    }
+}
+
+/// Finds tenant columns of scoped tables compared to a literal of another tenant.
+struct TenantCheck<'a> {
+    tables: &'a [String],
+    column: &'a str,
+    tenant: Value,
+    kinds: Vec<PredicateKind>,
+    pending: Option<String>,
+    conflict: Option<String>
+}
+
+impl<'a> TenantCheck<'a> {
+    fn result(&self) -> Result<(), TenantError> {
+        match self.conflict {
+            Some(ref table) => Err(TenantError::ConflictingTenant(table.to_string())),
+            None => Ok(())
+        }
+    }
+}
+
+impl<'a> Visitor for TenantCheck<'a> {
+    fn visit_field(&mut self, field: &Field) {
+        // Only `tenant_id = value`, not negated or other comparisons
+        let compared = match self.kinds.last() {
+            Some(kind) => *kind == PredicateKind::Is && !self.kinds.contains(&PredicateKind::Exclude),
+            None => false
+        };
+
+        self.pending = if compared && field.name() == self.column && self.tables.iter().any(|t| t == field.table_name()) {
+            Some(field.table_name().to_string())
+        } else {
+            None
+        };
+    }
+
+    fn visit_predicate(&mut self, kind: PredicateKind) {
+        self.kinds.push(kind);
+        self.pending = None;
+    }
+
+    fn leave_predicate(&mut self, _kind: PredicateKind) {
+        self.kinds.pop();
+        self.pending = None;
+    }
+
+    fn visit_value(&mut self, value: &Value) {
+        if let Some(table) = self.pending.take() {
+            if *value != self.tenant && self.conflict.is_none() {
+                self.conflict = Some(table);
+            }
+        }
+    }
+
+    fn visit_placeholder(&mut self, _placeholder: &placeholder::Placeholder) {
+        self.pending = None;
+    }
+
+    fn visit_raw(&mut self, _content: &str) {
+        self.pending = None;
+    }
+}
+
+/// Value assigned to a column in SET or VALUES, the first visited field is the column.
+struct AssignedValue {
+    field: Option<(String, String)>,
+    values: Vec<Value>,
+    other: bool
+}
+
+impl AssignedValue {
+    fn new() -> AssignedValue {
+        AssignedValue { field: None, values: vec![], other: false }
+    }
+
+    fn of_field(field: &Field) -> AssignedValue {
+        AssignedValue { field: Some((field.table_name().to_string(), field.name().to_string())), values: vec![], other: false }
+    }
+
+    /// Placeholders, columns, expressions and DEFAULT are not the literal.
+    fn is_literal(&self, value: &Value) -> bool {
+        !self.other && self.values.len() == 1 && self.values[0] == *value
+    }
+}
+
+impl Visitor for AssignedValue {
+    fn visit_table(&mut self, _table: &Table) {
+        self.other = true;
+    }
+
+    fn visit_field(&mut self, field: &Field) {
+        if self.field.is_none() {
+            self.field = Some((field.table_name().to_string(), field.name().to_string()));
+        } else {
+            self.other = true;
+        }
+    }
+
+    fn visit_predicate(&mut self, _kind: PredicateKind) {
+        self.other = true;
+    }
+
+    fn visit_value(&mut self, value: &Value) {
+        self.values.push(value.clone());
+    }
+
+    fn visit_placeholder(&mut self, _placeholder: &placeholder::Placeholder) {
+        self.other = true;
+    }
+
+    fn visit_raw(&mut self, _content: &str) {
+        self.other = true;
+    }
+}
+
+/// Finds raw SQL selecting from a scoped table, there is nothing to add the filter to.
+struct RawCheck<'a> {
+    tables: &'a [String],
+    unscopable: Option<String>
+}
+
+impl<'a> Visitor for RawCheck<'a> {
+    fn visit_raw(&mut self, content: &str) {
+        if self.unscopable.is_some() {
+            return
+        }
+
+        let words: Vec<String> = content.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map(|word| word.to_lowercase())
+            .collect();
+
+        if words.iter().any(|word| word == "select") {
+            self.unscopable = self.tables.iter().find(|table| words.contains(&table.to_lowercase())).cloned();
+        }
+    }
+}
+
+/// Side of a join whose rows are kept without a match.
+enum JoinSide {
+    Neither,
+    Left,
+    Right,
+    Both
+}
+
+fn preserved_side(join: &join::Join) -> JoinSide {
+    match join {
+        &join::Join::ConditionedJoin{ref join_type, ..} | &join::Join::UsingJoin{ref join_type, ..} => match join_type {
+            &join::ConditionedJoinType::InnerJoin => JoinSide::Neither,
+            &join::ConditionedJoinType::LeftJoin | &join::ConditionedJoinType::LeftOuterJoin => JoinSide::Left,
+            &join::ConditionedJoinType::RightJoin | &join::ConditionedJoinType::RightOuterJoin => JoinSide::Right,
+            &join::ConditionedJoinType::FullJoin | &join::ConditionedJoinType::FullOuterJoin => JoinSide::Both
+        },
+        &join::Join::UnconditionedJoin{ref join_type, ..} => match join_type {
+            &join::UnconditionedJoinType::NaturalJoin | &join::UnconditionedJoinType::CrossJoin => JoinSide::Neither,
+            &join::UnconditionedJoinType::NaturalLeftJoin => JoinSide::Left,
+            &join::UnconditionedJoinType::NaturalRightJoin => JoinSide::Right,
+            &join::UnconditionedJoinType::NaturalFullJoin => JoinSide::Both
+        }
+    }
+}
+
+/// Scopes the query and every SELECT nested in it, the first error is kept.
+struct TenantRewriter<'a, I: 'a> {
+    scope: &'a TenantScope<I>,
+    error: Option<TenantError>
+}
+
+impl<'a, I> Rewriter for TenantRewriter<'a, I>
+    where I: ToValue + ToExpression<I> + ToExpression<RawExpression> + sql::ToPredicateValue + Clone + 'static {
+
+    fn rewrite_select(&mut self, query: &mut RewritableSelect) {
+        if self.error.is_none() {
+            self.error = self.scope.scope_filters(query).err();
+        }
+    }
+}
+
+/// Rewrites queries to touch only rows of one tenant in scoped tables.
+///
+/// `tenant_id = tenant` is added to WHERE for every scoped table in FROM (and UPDATE's FROM or
+/// DELETE's USING) and to ON of joins with scoped tables, in the query and in every SELECT nested
+/// in it. Inserts into scoped tables get the column filled. The preserved side of RIGHT JOIN is
+/// filtered in WHERE and the other one in its ON, FULL JOIN of a scoped table can't be scoped.
+#[derive(Clone)]
+pub struct TenantScope<I> {
+    tables: Vec<String>,
+    column: String,
+    tenant: I
+}
+
+impl<I> TenantScope<I>
+    where I: ToValue + ToExpression<I> + ToExpression<RawExpression> + sql::ToPredicateValue + Clone + 'static {
+
+    pub fn new(tables: &[&str], tenant: I) -> TenantScope<I> {
+        TenantScope {
+            tables: tables.iter().map(|t| t.to_string()).collect(),
+            column: "tenant_id".to_string(),
+            tenant: tenant
+        }
+    }
+
+    /// Name of the tenant column, `tenant_id` by default.
+    pub fn column(mut self, column: &str) -> TenantScope<I> {
+        self.column = column.to_string();
+        self
+    }
+
+    pub fn get_tables(&self) -> &Vec<String> { &self.tables }
+    pub fn get_column(&self) -> &String { &self.column }
+    pub fn get_tenant(&self) -> &I { &self.tenant }
+
+    fn is_scoped(&self, table_name: &str) -> bool {
+        self.tables.iter().any(|t| t == table_name)
+    }
+
+    fn checker(&self) -> TenantCheck {
+        TenantCheck {
+            tables: &self.tables,
+            column: &self.column,
+            tenant: self.tenant.to_value(),
+            kinds: vec![],
+            pending: None,
+            conflict: None
+        }
+    }
+
+    fn field(&self, table_name: &str) -> field::NamedField<I> {
+        field::NamedField::new(&self.column, table_name)
+    }
+
+    /// `tenant_id = tenant` qualified with the table alias, `None` for tables that are not scoped.
+    fn predicate(&self, table: &Table) -> Option<predicate::SharedPredicate> {
+        if !self.is_scoped(table.get_table_name()) {
+            return None
+        }
+
+        let qual = table.get_table_alias().as_ref().unwrap_or(table.get_table_name());
+        let field = self.field(table.get_table_name()).qual_with(qual);
+        Some(field.is(self.tenant.clone()))
+    }
+
+    fn scoped_predicate(&self, table: &Table) -> Option<(String, predicate::SharedPredicate)> {
+        self.predicate(table).map(|predicate| (table.get_table_name().to_string(), predicate))
+    }
+
+    fn tenant_value(&self) -> InsertValue<RawExpression> {
+        InsertValue::new(<I as ToExpression<RawExpression>>::as_expr(&self.tenant))
+    }
+
+    /// Values assigned to the tenant column of a scoped table must be the tenant literal.
+    fn check_assigned(&self, assigned: &AssignedValue) -> Result<(), TenantError> {
+        match assigned.field {
+            Some((ref table, ref column)) if *column == self.column && self.is_scoped(table) => {
+                if assigned.is_literal(&self.tenant.to_value()) {
+                    Ok(())
+                } else {
+                    Err(TenantError::ConflictingTenant(table.to_string()))
+                }
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Scopes SELECTs nested anywhere in the query, including the query itself when it's a SELECT.
+    fn scope_nested<Q: Visitable + Rewritable + Clone>(&self, query: &Q) -> Result<Q, TenantError> {
+        let mut raw = RawCheck { tables: &self.tables, unscopable: None };
+        query.accept(&mut raw);
+        if let Some(table) = raw.unscopable {
+            return Err(TenantError::Unscopable(table))
+        }
+
+        let mut query = query.clone();
+        let error = {
+            let mut rewriter = TenantRewriter { scope: self, error: None };
+            query.rewrite(&mut rewriter);
+            rewriter.error
+        };
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(query)
+        }
+    }
+
+    /// Checks WHERE and ON of one SELECT and adds the filters for its FROM and joins.
+    fn scope_filters(&self, query: &mut RewritableSelect) -> Result<(), TenantError> {
+        {
+            let mut checker = self.checker();
+            if let &mut Some(ref where_) = query.get_where_mut() {
+                where_.accept_predicate(&mut checker);
+            }
+            for join in query.get_joins_mut().iter() {
+                if let &join::Join::ConditionedJoin{ref on, ..} = join {
+                    on.accept_predicate(&mut checker);
+                }
+            }
+            try!(checker.result());
+        }
+
+        // Filters of the tables before the current join, they end up in WHERE
+        let mut filters = vec![];
+
+        if let Some(table) = query.get_from().as_table() {
+            filters.extend(self.scoped_predicate(table));
+        }
+
+        // Derived tables and LATERAL are scoped on their own
+        for join in query.get_joins_mut().iter_mut() {
+            let scoped = join.get_from().as_table().and_then(|table| self.scoped_predicate(table));
+
+            match (preserved_side(join), join) {
+                // ON doesn't filter the preserved side and WHERE would drop the NULL rows of the other one
+                (JoinSide::Both, _) => {
+                    if let Some((table_name, _)) = scoped.or(filters.pop()) {
+                        return Err(TenantError::Unscopable(table_name))
+                    }
+                },
+                (JoinSide::Right, &mut join::Join::ConditionedJoin{ref mut on, ..}) => {
+                    for (_, filter) in filters.drain(..) {
+                        let scoped_on = on.and(filter);
+                        *on = scoped_on;
+                    }
+                    filters.extend(scoped);
+                },
+                (JoinSide::Right, _) => {
+                    if let Some(&(ref table_name, _)) = filters.first() {
+                        return Err(TenantError::Unscopable(table_name.to_string()))
+                    }
+                    filters.extend(scoped);
+                },
+                (JoinSide::Neither, &mut join::Join::ConditionedJoin{ref mut on, ..}) |
+                (JoinSide::Left, &mut join::Join::ConditionedJoin{ref mut on, ..}) => {
+                    if let Some((_, scoped)) = scoped {
+                        let scoped_on = on.and(scoped);
+                        *on = scoped_on;
+                    }
+                },
+                (JoinSide::Neither, _) => filters.extend(scoped),
+                (JoinSide::Left, _) => {
+                    if let Some((table_name, _)) = scoped {
+                        return Err(TenantError::Unscopable(table_name))
+                    }
+                }
+            }
+        }
+
+        let where_ = query.get_where_mut();
+        for (_, filter) in filters.into_iter() {
+            *where_ = Some(match where_.take() {
+                Some(predicate) => predicate.and(filter),
+                None => filter
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Scopes the query and every SELECT nested in it: derived tables, LATERAL, arguments of
+    /// table functions and subqueries in the select list, WHERE, HAVING and ON.
+    pub fn scope_select<T, L, M>(&self, query: &select_query::SelectQuery<T, L, M>) -> Result<select_query::SelectQuery<T, L, M>, TenantError>
+        where T: Clone, L: Clone, M: Clone {
+
+        self.scope_nested(query)
+    }
+
+    pub fn scope_update<T, L, M>(&self, query: &update_query::UpdateQuery<T, L, M>) -> Result<update_query::UpdateQuery<T, L, M>, TenantError>
+        where T: Clone, L: Clone, M: Clone {
+
+        let mut query = try!(self.scope_nested(query));
+
+        for update in query.get_updates().iter() {
+            let mut assigned = AssignedValue::new();
+            update.accept_update(&mut assigned);
+            try!(self.check_assigned(&assigned));
+        }
+
+        let mut checker = self.checker();
+        if let &Some(ref where_) = query.get_where() {
+            where_.accept_predicate(&mut checker);
+        }
+        try!(checker.result());
+
+        // Without WHERE and all() the query doesn't touch any row
+        if query.get_where().is_none() && !query.is_all() {
+            return Ok(query)
+        }
+
+        let mut filters = vec![];
+        filters.extend(self.predicate(&***query.get_table()));
+        if let &Some(ref from) = query.get_from() {
+            for from in from.iter() {
+                filters.extend(from.as_table().and_then(|table| self.predicate(table)));
+            }
+        }
+
+        for filter in filters.into_iter() {
+            query = query.and(filter);
+        }
+
+        Ok(query)
+    }
+
+    pub fn scope_delete<T, L, M>(&self, query: &delete_query::DeleteQuery<T, L, M>) -> Result<delete_query::DeleteQuery<T, L, M>, TenantError>
+        where T: Clone, L: Clone, M: Clone {
+
+        let mut query = try!(self.scope_nested(query));
+
+        let mut checker = self.checker();
+        if let &Some(ref where_) = query.get_where() {
+            where_.accept_predicate(&mut checker);
+        }
+        try!(checker.result());
+
+        // Without WHERE and all() the query doesn't touch any row
+        if query.get_where().is_none() && !query.is_all() {
+            return Ok(query)
+        }
+
+        let mut filters = vec![];
+        filters.extend(self.predicate(&***query.get_table()));
+        if let &Some(ref using) = query.get_using() {
+            for from in using.iter() {
+                filters.extend(from.as_table().and_then(|table| self.predicate(table)));
+            }
+        }
+
+        for filter in filters.into_iter() {
+            query = query.and(filter);
+        }
+
+        Ok(query)
+    }
+
+    /// Fills the tenant column of DEFAULT VALUES and of untyped VALUES without it. Where the column
+    /// is inserted its value must be the tenant literal, DEFAULT is replaced with it. INSERT ... SELECT
+    /// gets its SELECT scoped, typed VALUES can't be extended with a column.
+    pub fn scope_insert<T, V, M, RT, RL>(&self, query: &insert_query::InsertQuery<T, V, M, RT, RL>) -> Result<insert_query::InsertQuery<T, V, M, RT, RL>, TenantError>
+        where T: Clone, V: Clone + Visitable + Rewritable + InsertRow, M: Clone, RT: Clone, RL: Clone {
+
+        let mut query = try!(self.scope_nested(query));
+
+        let table_name = query.get_into().get_table_name().to_string();
+        if !self.is_scoped(&table_name) {
+            return Ok(query)
+        }
+
+        let field = self.field(&table_name);
+        let idx = match query.get_cols() {
+            &Some(ref cols) => cols.iter().position(|col| col.name() == self.column),
+            &None => None
+        };
+
+        let values = query.get_values().clone();
+        match values {
+            insert_query::Insert::DefaultValues => {
+                *query.get_cols_mut() = Some(vec![field.upcast_field()]);
+                *query.get_values_mut() = insert_query::Insert::UntypedValues(vec![vec![self.tenant_value()]]);
+            },
+            insert_query::Insert::UntypedValues(mut rows) => {
+                match idx {
+                    Some(idx) => {
+                        for row in rows.iter_mut() {
+                            let is_default = match row[idx] {
+                                InsertValue::Default => true,
+                                _ => false
+                            };
+
+                            if is_default {
+                                row[idx] = self.tenant_value();
+                            } else {
+                                let mut assigned = AssignedValue::of_field(&field);
+                                row[idx].accept(&mut assigned);
+                                try!(self.check_assigned(&assigned));
+                            }
+                        }
+                    },
+                    None if query.get_cols().is_some() => {
+                        for row in rows.iter_mut() {
+                            row.push(self.tenant_value());
+                        }
+                        query.get_cols_mut().as_mut().unwrap().push(field.upcast_field());
+                    },
+                    // Values are positional, the tenant column can't be placed
+                    None => return Err(TenantError::Unscopable(table_name))
+                }
+                *query.get_values_mut() = insert_query::Insert::UntypedValues(rows);
+            },
+            insert_query::Insert::FromSelect(select) => {
+                let expr = match (idx, select.get_select()) {
+                    (Some(idx), &select_query::Select::Only(ref exprs)) => exprs.get(idx).cloned(),
+                    _ => None
+                };
+
+                // The value of the column is unknown without it in the select list
+                let expr = match expr {
+                    Some(expr) => expr,
+                    None => return Err(TenantError::Unscopable(table_name))
+                };
+
+                let mut assigned = AssignedValue::of_field(&field);
+                expr.accept_expression(&mut assigned);
+                try!(self.check_assigned(&assigned));
+            },
+            insert_query::Insert::Values(mut rows) => {
+                let idx = match idx {
+                    Some(idx) => idx,
+                    None => return Err(TenantError::Unscopable(table_name))
+                };
+
+                for row in rows.iter_mut() {
+                    let value = match row.get_row_values_mut().into_iter().nth(idx) {
+                        Some(value) => value,
+                        None => return Err(TenantError::Unscopable(table_name))
+                    };
+
+                    let expression = value.get_expression().cloned();
+                    match expression {
+                        Some(expression) => {
+                            let mut assigned = AssignedValue::of_field(&field);
+                            expression.accept_expression(&mut assigned);
+                            try!(self.check_assigned(&assigned));
+                        },
+                        None => value.set_expression(<I as ToExpression<I>>::as_expr(&self.tenant).upcast_expression())
+                    }
+                }
+                *query.get_values_mut() = insert_query::Insert::Values(rows);
+            }
+        }
+
+        Ok(query)
+    }
+}
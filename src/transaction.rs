@@ -0,0 +1,40 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable
+}
+
+/// Characteristics of a transaction, renders to its `BEGIN` statement.
+#[derive(Clone, Default)]
+pub struct TransactionOptions {
+    isolation: Option<IsolationLevel>,
+    read_only: bool,
+    deferrable: bool
+}
+
+impl TransactionOptions {
+    pub fn get_isolation(&self) -> &Option<IsolationLevel> { &self.isolation }
+    pub fn is_read_only(&self) -> bool { self.read_only }
+    pub fn is_deferrable(&self) -> bool { self.deferrable }
+
+    pub fn new() -> TransactionOptions {
+        TransactionOptions::default()
+    }
+
+    pub fn isolation(mut self, isolation: IsolationLevel) -> TransactionOptions {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    pub fn read_only(mut self) -> TransactionOptions {
+        self.read_only = true;
+        self
+    }
+
+    pub fn deferrable(mut self) -> TransactionOptions {
+        self.deferrable = true;
+        self
+    }
+}
@@ -0,0 +1,73 @@
+use super::from;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TruncateIdentity {
+    Restart,
+    Continue
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TruncateBehavior {
+    Cascade,
+    Restrict
+}
+
+pub trait Truncatable: from::Table + Sized {
+    fn truncate(&self) -> TruncateQuery {
+        TruncateQuery::new(self)
+    }
+}
+
+#[derive(Clone)]
+pub struct TruncateQuery {
+    only: bool,
+    tables: Vec<from::SharedTable>,
+    identity: Option<TruncateIdentity>,
+    behavior: Option<TruncateBehavior>
+}
+
+impl TruncateQuery {
+    pub fn is_only(&self) -> bool { self.only }
+    pub fn get_tables(&self) -> &Vec<from::SharedTable> { &self.tables }
+    pub fn get_identity(&self) -> &Option<TruncateIdentity> { &self.identity }
+    pub fn get_behavior(&self) -> &Option<TruncateBehavior> { &self.behavior }
+
+    pub fn new(table: &from::Table) -> TruncateQuery {
+        TruncateQuery {
+            only: false,
+            tables: vec![table.upcast_table()],
+            identity: None,
+            behavior: None
+        }
+    }
+
+    pub fn table(mut self, table: &from::Table) -> TruncateQuery {
+        self.tables.push(table.upcast_table());
+        self
+    }
+
+    pub fn only(mut self) -> TruncateQuery {
+        self.only = true;
+        self
+    }
+
+    pub fn restart_identity(mut self) -> TruncateQuery {
+        self.identity = Some(TruncateIdentity::Restart);
+        self
+    }
+
+    pub fn continue_identity(mut self) -> TruncateQuery {
+        self.identity = Some(TruncateIdentity::Continue);
+        self
+    }
+
+    pub fn cascade(mut self) -> TruncateQuery {
+        self.behavior = Some(TruncateBehavior::Cascade);
+        self
+    }
+
+    pub fn restrict(mut self) -> TruncateQuery {
+        self.behavior = Some(TruncateBehavior::Restrict);
+        self
+    }
+}
@@ -6,12 +6,20 @@ use super::select_query;
 use super::insert_query::{self, ToInsertValue};
 use super::from;
 use super::predicate;
+use super::order_by;
 use super::expression;
 use super::sql;
 use super::field;
+use super::visitor::{Visitor, Visitable, Rewriter, Rewritable};
 
 pub trait FieldUpd: sql::ToSql {
     fn upcast_field_update(&self) -> SharedFieldUpdate;
+
+    /// Walks the updated field and the new value.
+    fn accept_update(&self, visitor: &mut Visitor);
+
+    /// Rewrites subqueries of the new value.
+    fn rewrite_update(&mut self, rewriter: &mut Rewriter);
 }
 
 #[derive(Clone)]
@@ -39,6 +47,16 @@ impl<F, T> FieldUpd for FieldUpdate<F, T>
     fn upcast_field_update(&self) -> SharedFieldUpdate {
         rc::Rc::new(Box::new(self.clone()))
     }
+
+    fn accept_update(&self, visitor: &mut Visitor) {
+        self.field.accept_value(visitor);
+        self.value.accept(visitor);
+    }
+
+    fn rewrite_update(&mut self, rewriter: &mut Rewriter) {
+        self.field.rewrite_value(rewriter);
+        self.value.rewrite(rewriter);
+    }
 }
 
 pub trait ToFieldUpdate<F, T> {
@@ -93,6 +111,8 @@ pub struct UpdateQuery<T, L, M> {
     where_: Option<predicate::SharedPredicate>,
     all: bool,
     returning: Option<select_query::Select>,
+    order_by: Vec<order_by::OrderBy>,
+    limit: Option<usize>,
 
     _marker_t: marker::PhantomData<T>,
     _marker_l: marker::PhantomData<L>,
@@ -108,6 +128,13 @@ impl<T, L, M> UpdateQuery<T, L, M> {
     pub fn get_from(&self) -> &Option<Vec<from::SharedFrom>> { &self.from }
     pub fn get_where(&self) -> &Option<predicate::SharedPredicate> { &self.where_ }
     pub fn get_returning(&self) -> &Option<select_query::Select> { &self.returning }
+    pub fn get_order_by(&self) -> &Vec<order_by::OrderBy> { &self.order_by }
+    pub fn get_limit(&self) -> &Option<usize> { &self.limit }
+
+    pub fn get_updates_mut(&mut self) -> &mut Vec<SharedFieldUpdate> { &mut self.updates }
+    pub fn get_from_mut(&mut self) -> &mut Option<Vec<from::SharedFrom>> { &mut self.from }
+    pub fn get_where_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { &mut self.where_ }
+    pub fn get_returning_mut(&mut self) -> &mut Option<select_query::Select> { &mut self.returning }
 }
 
 impl<T, L, M> UpdateQuery<T, L, M> {
@@ -120,6 +147,8 @@ impl<T, L, M> UpdateQuery<T, L, M> {
             where_: None,
             all: false,
             returning: None,
+            order_by: vec![],
+            limit: None,
 
             _marker_t: marker::PhantomData,
             _marker_l: marker::PhantomData,
@@ -151,6 +180,11 @@ impl<T, L, M> UpdateQuery<T, L, M> {
         self.all = true;
         self
     }
+
+    pub fn limit(mut self, limit: usize) -> UpdateQuery<T, L, M> {
+        self.limit = Some(limit);
+        self
+    }
 }
 
 returning_for!(UpdateQuery);
@@ -160,3 +194,8 @@ impl<T:Clone, L:Clone, M:Clone> select_query::Queryable for UpdateQuery<T, L, M>
     fn set_where(&mut self, predicate: predicate::SharedPredicate) { self.where_ = Some(predicate); }
     fn unset_where(&mut self) { self.where_ = None; }
 }
+
+impl<T:Clone, L:Clone, M:Clone> select_query::Orderable for UpdateQuery<T, L, M> {
+    fn get_order_by_mut(&mut self) -> &mut Vec<order_by::OrderBy> { &mut self.order_by }
+    fn set_order_by(&mut self, order_by: Vec<order_by::OrderBy>) { self.order_by = order_by }
+}
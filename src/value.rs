@@ -0,0 +1,171 @@
+use serialize::json;
+use time;
+use uuid;
+
+use super::sql;
+
+/// Driver independent column value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Json(json::Json),
+    Timespec(time::Timespec),
+    Uuid(uuid::Uuid),
+    /// Exact decimal in text form, e.g. PostgreSQL NUMERIC.
+    Numeric(String),
+    /// One-dimensional array, elements are of one type or NULL.
+    Array(Vec<Value>)
+}
+
+impl Value {
+    /// Value for binding with `SqlContext::bind`.
+    pub fn to_boxed(&self) -> sql::BoxedValue {
+        self.to_boxed_as(None)
+    }
+
+    /// Like `to_boxed`, NULL is typed as `value_type` (see `ValueType`) to match its placeholder.
+    pub fn to_boxed_as(&self, value_type: Option<&str>) -> sql::BoxedValue {
+        match self {
+            &Value::Null => null_to_boxed(value_type),
+            &Value::Bool(ref v) => Box::new(v.clone()),
+            &Value::I8(ref v) => Box::new(v.clone()),
+            &Value::I16(ref v) => Box::new(v.clone()),
+            &Value::I32(ref v) => Box::new(v.clone()),
+            &Value::I64(ref v) => Box::new(v.clone()),
+            &Value::F32(ref v) => Box::new(v.clone()),
+            &Value::F64(ref v) => Box::new(v.clone()),
+            &Value::String(ref v) => Box::new(v.clone()),
+            &Value::Bytes(ref v) => Box::new(v.clone()),
+            &Value::Json(ref v) => Box::new(v.clone()),
+            &Value::Timespec(ref v) => Box::new(v.clone()),
+            &Value::Uuid(ref v) => Box::new(v.clone()),
+            // Bound as text, a placeholder of a NUMERIC parameter needs a cast
+            &Value::Numeric(ref v) => Box::new(v.clone()),
+            &Value::Array(ref v) => array_to_boxed(v),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            &Value::Null => "NULL",
+            &Value::Bool(_) => "bool",
+            &Value::I8(_) => "i8",
+            &Value::I16(_) => "i16",
+            &Value::I32(_) => "i32",
+            &Value::I64(_) => "i64",
+            &Value::F32(_) => "f32",
+            &Value::F64(_) => "f64",
+            &Value::String(_) => "String",
+            &Value::Bytes(_) => "Vec<u8>",
+            &Value::Json(_) => "Json",
+            &Value::Timespec(_) => "Timespec",
+            &Value::Uuid(_) => "Uuid",
+            &Value::Numeric(_) => "Numeric",
+            &Value::Array(_) => "Array",
+        }
+    }
+}
+
+// Untyped NULL is rendered inline by queries, text only makes it bindable
+fn null_to_boxed(value_type: Option<&str>) -> sql::BoxedValue {
+    match value_type {
+        Some("bool") => Box::new(None::<bool>),
+        Some("i8") => Box::new(None::<i8>),
+        Some("i16") => Box::new(None::<i16>),
+        Some("i32") => Box::new(None::<i32>),
+        Some("i64") => Box::new(None::<i64>),
+        Some("f32") => Box::new(None::<f32>),
+        Some("f64") => Box::new(None::<f64>),
+        Some("Vec<u8>") => Box::new(None::<Vec<u8>>),
+        Some("Json") => Box::new(None::<json::Json>),
+        Some("Timespec") => Box::new(None::<time::Timespec>),
+        Some("Uuid") => Box::new(None::<uuid::Uuid>),
+        _ => Box::new(None::<String>)
+    }
+}
+
+macro_rules! boxed_array {
+    ($values:expr, $variant:ident, $t:ty) => ({
+        let values: Vec<Option<$t>> = $values.iter().map(|v| match v {
+            &Value::$variant(ref v) => Some(v.clone()),
+            _ => None
+        }).collect();
+        Box::new(values)
+    })
+}
+
+// Element type is taken from the first non-NULL element
+fn array_to_boxed(values: &[Value]) -> sql::BoxedValue {
+    match values.iter().find(|v| **v != Value::Null) {
+        Some(&Value::Bool(_)) => boxed_array!(values, Bool, bool),
+        Some(&Value::I8(_)) => boxed_array!(values, I8, i8),
+        Some(&Value::I16(_)) => boxed_array!(values, I16, i16),
+        Some(&Value::I32(_)) => boxed_array!(values, I32, i32),
+        Some(&Value::I64(_)) => boxed_array!(values, I64, i64),
+        Some(&Value::F32(_)) => boxed_array!(values, F32, f32),
+        Some(&Value::F64(_)) => boxed_array!(values, F64, f64),
+        Some(&Value::Bytes(_)) => boxed_array!(values, Bytes, Vec<u8>),
+        Some(&Value::Json(_)) => boxed_array!(values, Json, json::Json),
+        Some(&Value::Timespec(_)) => boxed_array!(values, Timespec, time::Timespec),
+        Some(&Value::Uuid(_)) => boxed_array!(values, Uuid, uuid::Uuid),
+        Some(&Value::Numeric(_)) => boxed_array!(values, Numeric, String),
+        _ => boxed_array!(values, String, String)
+    }
+}
+
+/// Converts bound values into driver independent values.
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+/// Name of the value type, as in `Value::type_name()`.
+pub trait ValueType {
+    fn value_type() -> &'static str;
+}
+
+macro_rules! to_value {
+    ($t:ty, $variant:ident, $name:expr) => (
+        impl ToValue for $t {
+            fn to_value(&self) -> Value { Value::$variant(self.clone()) }
+        }
+
+        impl ValueType for $t {
+            fn value_type() -> &'static str { $name }
+        }
+    )
+}
+
+to_value!(bool, Bool, "bool");
+to_value!(i8, I8, "i8");
+to_value!(i16, I16, "i16");
+to_value!(i32, I32, "i32");
+to_value!(i64, I64, "i64");
+to_value!(f32, F32, "f32");
+to_value!(f64, F64, "f64");
+to_value!(String, String, "String");
+to_value!(Vec<u8>, Bytes, "Vec<u8>");
+to_value!(json::Json, Json, "Json");
+to_value!(time::Timespec, Timespec, "Timespec");
+to_value!(uuid::Uuid, Uuid, "Uuid");
+
+impl<T: ValueType> ValueType for Option<T> {
+    fn value_type() -> &'static str { T::value_type() }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            &Some(ref value) => value.to_value(),
+            &None => Value::Null
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use std::rc;
+
+use super::select_query;
+use super::insert_query;
+use super::update_query;
+use super::delete_query;
+use super::from;
+use super::field;
+use super::join;
+use super::group_by;
+use super::order_by;
+use super::placeholder;
+use super::predicate;
+use super::expression;
+use super::value::{Value};
+
+/// Predicate node passed to `Visitor::visit_predicate`, operands are visited before `leave_predicate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PredicateKind {
+    Is,
+    IsNull,
+    In,
+    InRange,
+    Inequality,
+    Like,
+    And,
+    Or,
+    Exclude,
+    Raw
+}
+
+/// Callbacks for walking a query, every callback does nothing by default.
+///
+/// Subqueries (e.g. `IN (SELECT ...)` or derived tables) are walked in place.
+pub trait Visitor {
+    fn visit_table(&mut self, _table: &from::Table) {}
+    fn visit_field(&mut self, _field: &field::Field) {}
+    fn visit_predicate(&mut self, _kind: PredicateKind) {}
+    fn leave_predicate(&mut self, _kind: PredicateKind) {}
+    /// Value bound to the query, NULL for `None`.
+    fn visit_value(&mut self, _value: &Value) {}
+    fn visit_placeholder(&mut self, _placeholder: &placeholder::Placeholder) {}
+    /// Content of raw expressions and predicates.
+    fn visit_raw(&mut self, _content: &str) {}
+}
+
+pub trait Visitable {
+    fn accept(&self, visitor: &mut Visitor);
+}
+
+/// SELECT passed to `Rewriter::rewrite_select`, whatever its result type is.
+pub trait RewritableSelect {
+    fn get_from(&self) -> &from::SharedFrom;
+    fn get_joins_mut(&mut self) -> &mut Vec<join::Join>;
+    fn get_select_mut(&mut self) -> &mut select_query::Select;
+    fn get_where_mut(&mut self) -> &mut Option<predicate::SharedPredicate>;
+    fn get_having_mut(&mut self) -> &mut Option<predicate::SharedPredicate>;
+}
+
+/// Changes queries while they are walked by `Rewritable::rewrite`.
+pub trait Rewriter {
+    /// Called for the query and every SELECT nested in it (derived tables, subqueries in
+    /// expressions, predicates and SET), inner queries before the ones they are part of.
+    fn rewrite_select(&mut self, query: &mut RewritableSelect);
+}
+
+/// Queries that can be changed by a `Rewriter`.
+///
+/// Parts of a query are shared with the queries it was built from, rewritten parts are copied
+/// first, so the other queries stay as they are.
+pub trait Rewritable {
+    fn rewrite(&mut self, rewriter: &mut Rewriter);
+}
+
+pub fn rewrite_shared_expression(expression: &mut expression::SharedExpression, rewriter: &mut Rewriter) {
+    let mut copy = expression.upcast_expression();
+    rc::Rc::get_mut(&mut copy).expect("upcast_expression returns a new expression").rewrite_expression(rewriter);
+    *expression = copy;
+}
+
+pub fn rewrite_shared_expressions(expressions: &mut Vec<expression::SharedExpression>, rewriter: &mut Rewriter) {
+    for expression in expressions.iter_mut() {
+        rewrite_shared_expression(expression, rewriter);
+    }
+}
+
+pub fn rewrite_shared_from(from: &mut from::SharedFrom, rewriter: &mut Rewriter) {
+    let mut copy = from.upcast_from();
+    rc::Rc::get_mut(&mut copy).expect("upcast_from returns a new source").rewrite_from(rewriter);
+    *from = copy;
+}
+
+pub fn rewrite_shared_predicate(predicate: &mut predicate::SharedPredicate, rewriter: &mut Rewriter) {
+    let mut copy = predicate.upcast_predicate();
+    rc::Rc::get_mut(&mut copy).expect("upcast_predicate returns a new predicate").rewrite_predicate(rewriter);
+    *predicate = copy;
+}
+
+fn rewrite_froms(froms: &mut Option<Vec<from::SharedFrom>>, rewriter: &mut Rewriter) {
+    if let &mut Some(ref mut froms) = froms {
+        for from in froms.iter_mut() {
+            rewrite_shared_from(from, rewriter);
+        }
+    }
+}
+
+fn rewrite_predicate(predicate: &mut Option<predicate::SharedPredicate>, rewriter: &mut Rewriter) {
+    if let &mut Some(ref mut predicate) = predicate {
+        rewrite_shared_predicate(predicate, rewriter);
+    }
+}
+
+pub fn rewrite_order_by(order_by: &mut Vec<order_by::OrderBy>, rewriter: &mut Rewriter) {
+    for order in order_by.iter_mut() {
+        rewrite_shared_expression(order.get_by_mut(), rewriter);
+    }
+}
+
+fn rewrite_returning(returning: &mut Option<select_query::Select>, rewriter: &mut Rewriter) {
+    if let &mut Some(ref mut select) = returning {
+        select.rewrite(rewriter);
+    }
+}
+
+fn accept_expressions(expressions: &[expression::SharedExpression], visitor: &mut Visitor) {
+    for expression in expressions.iter() {
+        expression.accept_expression(visitor);
+    }
+}
+
+fn accept_froms(froms: &Option<Vec<from::SharedFrom>>, visitor: &mut Visitor) {
+    if let &Some(ref froms) = froms {
+        for from in froms.iter() {
+            from.accept_from(visitor);
+        }
+    }
+}
+
+fn accept_predicate(predicate: &Option<predicate::SharedPredicate>, visitor: &mut Visitor) {
+    if let &Some(ref predicate) = predicate {
+        predicate.accept_predicate(visitor);
+    }
+}
+
+fn accept_order_by(order_by: &Vec<order_by::OrderBy>, visitor: &mut Visitor) {
+    for order in order_by.iter() {
+        order.get_by().accept_expression(visitor);
+    }
+}
+
+fn accept_returning(returning: &Option<select_query::Select>, visitor: &mut Visitor) {
+    if let &Some(ref select) = returning {
+        select.accept(visitor);
+    }
+}
+
+impl Visitable for select_query::Select {
+    fn accept(&self, visitor: &mut Visitor) {
+        match self {
+            &select_query::Select::Only(ref exprs) => accept_expressions(exprs, visitor),
+            &select_query::Select::All => ()
+        }
+    }
+}
+
+impl Rewritable for select_query::Select {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        match self {
+            &mut select_query::Select::Only(ref mut exprs) => rewrite_shared_expressions(exprs, rewriter),
+            &mut select_query::Select::All => ()
+        }
+    }
+}
+
+impl Visitable for join::Join {
+    fn accept(&self, visitor: &mut Visitor) {
+        self.get_from().accept_from(visitor);
+        match self {
+            &join::Join::ConditionedJoin{ref on, ..} => on.accept_predicate(visitor),
+            &join::Join::UnconditionedJoin{..} => (),
+            &join::Join::UsingJoin{ref using, ..} => {
+                for field in using.iter() {
+                    visitor.visit_field(&***field);
+                }
+            }
+        }
+    }
+}
+
+impl Rewritable for join::Join {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        rewrite_shared_from(self.get_from_mut(), rewriter);
+        if let &mut join::Join::ConditionedJoin{ref mut on, ..} = self {
+            rewrite_shared_predicate(on, rewriter);
+        }
+    }
+}
+
+impl Visitable for group_by::GroupBy {
+    fn accept(&self, visitor: &mut Visitor) {
+        accept_expressions(self.get_by(), visitor);
+        for element in self.get_grouping().iter() {
+            match element {
+                &group_by::GroupingElement::Rollup(ref exprs) |
+                &group_by::GroupingElement::Cube(ref exprs) => accept_expressions(exprs, visitor),
+                &group_by::GroupingElement::GroupingSets(ref sets) => {
+                    for set in sets.iter() {
+                        accept_expressions(set, visitor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Rewritable for group_by::GroupBy {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        rewrite_shared_expressions(self.get_by_mut(), rewriter);
+        for element in self.get_grouping_mut().iter_mut() {
+            match element {
+                &mut group_by::GroupingElement::Rollup(ref mut exprs) |
+                &mut group_by::GroupingElement::Cube(ref mut exprs) => rewrite_shared_expressions(exprs, rewriter),
+                &mut group_by::GroupingElement::GroupingSets(ref mut sets) => {
+                    for set in sets.iter_mut() {
+                        rewrite_shared_expressions(set, rewriter);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T, L, M> Visitable for select_query::SelectQuery<T, L, M> {
+    fn accept(&self, visitor: &mut Visitor) {
+        if let &Some(ref distinct) = self.get_distinct() {
+            if let &Some(ref on) = distinct.get_on() {
+                accept_expressions(on, visitor);
+            }
+        }
+
+        self.get_select().accept(visitor);
+        self.get_from().accept_from(visitor);
+
+        for join in self.get_joins().iter() {
+            join.accept(visitor);
+        }
+
+        accept_predicate(self.get_where(), visitor);
+
+        if let &Some(ref group_by) = self.get_group_by() {
+            group_by.accept(visitor);
+        }
+
+        accept_predicate(self.get_having(), visitor);
+        accept_order_by(self.get_order_by(), visitor);
+    }
+}
+
+impl<T: Clone, L: Clone, M: Clone> RewritableSelect for select_query::SelectQuery<T, L, M> {
+    fn get_from(&self) -> &from::SharedFrom { select_query::SelectQuery::get_from(self) }
+    fn get_joins_mut(&mut self) -> &mut Vec<join::Join> { select_query::SelectQuery::get_joins_mut(self) }
+    fn get_select_mut(&mut self) -> &mut select_query::Select { select_query::SelectQuery::get_select_mut(self) }
+    fn get_where_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { select_query::SelectQuery::get_where_mut(self) }
+    fn get_having_mut(&mut self) -> &mut Option<predicate::SharedPredicate> { select_query::SelectQuery::get_having_mut(self) }
+}
+
+impl<T: Clone, L: Clone, M: Clone> Rewritable for select_query::SelectQuery<T, L, M> {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        if let &mut Some(ref mut distinct) = self.get_distinct_mut() {
+            if let &mut Some(ref mut on) = distinct.get_on_mut() {
+                rewrite_shared_expressions(on, rewriter);
+            }
+        }
+
+        self.get_select_mut().rewrite(rewriter);
+        rewrite_shared_from(self.get_from_mut(), rewriter);
+
+        for join in self.get_joins_mut().iter_mut() {
+            join.rewrite(rewriter);
+        }
+
+        rewrite_predicate(self.get_where_mut(), rewriter);
+
+        if let &mut Some(ref mut group_by) = self.get_group_by_mut() {
+            group_by.rewrite(rewriter);
+        }
+
+        rewrite_predicate(self.get_having_mut(), rewriter);
+        rewrite_order_by(select_query::Orderable::get_order_by_mut(self), rewriter);
+
+        rewriter.rewrite_select(self);
+    }
+}
+
+impl<T, L, M> Visitable for update_query::UpdateQuery<T, L, M> {
+    fn accept(&self, visitor: &mut Visitor) {
+        visitor.visit_table(&***self.get_table());
+        for update in self.get_updates().iter() {
+            update.accept_update(visitor);
+        }
+        accept_froms(self.get_from(), visitor);
+        accept_predicate(self.get_where(), visitor);
+        accept_order_by(self.get_order_by(), visitor);
+        accept_returning(self.get_returning(), visitor);
+    }
+}
+
+impl<T: Clone, L: Clone, M: Clone> Rewritable for update_query::UpdateQuery<T, L, M> {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        for update in self.get_updates_mut().iter_mut() {
+            let mut copy = update.upcast_field_update();
+            rc::Rc::get_mut(&mut copy).expect("upcast_field_update returns a new update").rewrite_update(rewriter);
+            *update = copy;
+        }
+        rewrite_froms(self.get_from_mut(), rewriter);
+        rewrite_predicate(self.get_where_mut(), rewriter);
+        rewrite_order_by(select_query::Orderable::get_order_by_mut(self), rewriter);
+        rewrite_returning(self.get_returning_mut(), rewriter);
+    }
+}
+
+impl<T, L, M> Visitable for delete_query::DeleteQuery<T, L, M> {
+    fn accept(&self, visitor: &mut Visitor) {
+        visitor.visit_table(&***self.get_table());
+        accept_froms(self.get_using(), visitor);
+        accept_predicate(self.get_where(), visitor);
+        accept_order_by(self.get_order_by(), visitor);
+        accept_returning(self.get_returning(), visitor);
+    }
+}
+
+impl<T: Clone, L: Clone, M: Clone> Rewritable for delete_query::DeleteQuery<T, L, M> {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        rewrite_froms(self.get_using_mut(), rewriter);
+        rewrite_predicate(self.get_where_mut(), rewriter);
+        rewrite_order_by(select_query::Orderable::get_order_by_mut(self), rewriter);
+        rewrite_returning(self.get_returning_mut(), rewriter);
+    }
+}
+
+impl<T> Visitable for insert_query::InsertValue<T> {
+    fn accept(&self, visitor: &mut Visitor) {
+        match self {
+            &insert_query::InsertValue::Value{ref expression, ..} => expression.accept_expression(visitor),
+            &insert_query::InsertValue::Default => ()
+        }
+    }
+}
+
+impl<T> Rewritable for insert_query::InsertValue<T> {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        match self {
+            &mut insert_query::InsertValue::Value{ref mut expression, ..} => rewrite_shared_expression(expression, rewriter),
+            &mut insert_query::InsertValue::Default => ()
+        }
+    }
+}
+
+impl Visitable for () {
+    fn accept(&self, _visitor: &mut Visitor) {}
+}
+
+impl Rewritable for () {
+    fn rewrite(&mut self, _rewriter: &mut Rewriter) {}
+}
+
+macro_rules! visitable_insert_tuple {
+    ($($t:ident, $var:ident),+) => (
+        impl<$($t,)+> Visitable for ($(insert_query::InsertValue<$t>),+,) {
+            fn accept(&self, visitor: &mut Visitor) {
+                let &($(ref $var,)+) = self;
+                $($var.accept(visitor);)+
+            }
+        }
+    )
+}
+
+macro_rules! rewritable_insert_tuple {
+    ($($t:ident, $var:ident),+) => (
+        impl<$($t,)+> Rewritable for ($(insert_query::InsertValue<$t>),+,) {
+            fn rewrite(&mut self, rewriter: &mut Rewriter) {
+                let &mut ($(ref mut $var,)+) = self;
+                $($var.rewrite(rewriter);)+
+            }
+        }
+    )
+}
+
+visitable_insert_tuple!(T1, t1);
+visitable_insert_tuple!(T1, t1, T2, t2);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11);
+visitable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11, T12, t12);
+
+rewritable_insert_tuple!(T1, t1);
+rewritable_insert_tuple!(T1, t1, T2, t2);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11);
+rewritable_insert_tuple!(T1, t1, T2, t2, T3, t3, T4, t4, T5, t5, T6, t6, T7, t7, T8, t8, T9, t9, T10, t10, T11, t11, T12, t12);
+
+impl<T, V, M, RT, RL> Visitable for insert_query::InsertQuery<T, V, M, RT, RL>
+    where T: Clone, V: Clone + Visitable, M: Clone, RT: Clone, RL: Clone {
+    fn accept(&self, visitor: &mut Visitor) {
+        visitor.visit_table(&***self.get_into());
+
+        if let &Some(ref cols) = self.get_cols() {
+            for col in cols.iter() {
+                visitor.visit_field(&***col);
+            }
+        }
+
+        match self.get_values() {
+            &insert_query::Insert::DefaultValues => (),
+            &insert_query::Insert::Values(ref rows) => {
+                for row in rows.iter() {
+                    row.accept(visitor);
+                }
+            },
+            &insert_query::Insert::UntypedValues(ref rows) => {
+                for row in rows.iter() {
+                    for value in row.iter() {
+                        value.accept(visitor);
+                    }
+                }
+            },
+            &insert_query::Insert::FromSelect(ref select) => select.accept(visitor)
+        }
+
+        accept_returning(self.get_returning(), visitor);
+    }
+}
+
+impl<T, V, M, RT, RL> Rewritable for insert_query::InsertQuery<T, V, M, RT, RL>
+    where T: Clone, V: Clone + Rewritable, M: Clone, RT: Clone, RL: Clone {
+    fn rewrite(&mut self, rewriter: &mut Rewriter) {
+        match self.get_values_mut() {
+            &mut insert_query::Insert::DefaultValues => (),
+            &mut insert_query::Insert::Values(ref mut rows) => {
+                for row in rows.iter_mut() {
+                    row.rewrite(rewriter);
+                }
+            },
+            &mut insert_query::Insert::UntypedValues(ref mut rows) => {
+                for row in rows.iter_mut() {
+                    for value in row.iter_mut() {
+                        value.rewrite(rewriter);
+                    }
+                }
+            },
+            &mut insert_query::Insert::FromSelect(ref mut select) => select.rewrite(rewriter)
+        }
+
+        rewrite_returning(self.get_returning_mut(), rewriter);
+    }
+}
@@ -0,0 +1,50 @@
+use deuterium::*;
+
+#[test]
+fn select_alias() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let query: SelectQuery<(String,), LimitMany, ()> = jedi_table.select_1(&name.as_("jedi_name"));
+    assert_sql!(query, "SELECT name AS jedi_name FROM jedi;");
+
+    let total = CountAll.as_("total");
+    let query: SelectQuery<(bool, i64), LimitMany, ()> = jedi_table.select_2(&side, &total)
+        .group_by(&[&side])
+        .having(total.field().gt(10i64))
+        .order_by(&total.field());
+
+    assert_sql!(query, "SELECT side, COUNT(*) AS total FROM jedi GROUP BY side HAVING total > $1 ORDER BY total ASC;");
+}
+
+#[test]
+fn select_alias_from_select() {
+
+    let jedi_table = TableDef::new("jedi");
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let total = CountAll.as_("total");
+
+    let counts = jedi_table.select_2(&side, &total).group_by(&[&side]).from_as("c");
+    let query = counts.select_1(&counts.field(&total)).where_(counts.field(&total).gt(10i64));
+
+    assert_sql!(query, "SELECT c.total FROM (SELECT side, COUNT(*) AS total FROM jedi GROUP BY side) as c WHERE c.total > $1;");
+}
+
+#[test]
+fn alias_outside_select_list() {
+
+    let jedi_table = TableDef::new("jedi");
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let total = CountAll.as_("total");
+
+    let query: SelectQuery<(bool, i64), LimitMany, ()> = jedi_table.select_2(&side, &total)
+        .group_by(&[&side])
+        .order_by(&total);
+
+    assert_sql!(query, "SELECT side, COUNT(*) AS total FROM jedi GROUP BY side ORDER BY total ASC;");
+
+    let query: SelectQuery<(bool,), LimitMany, ()> = jedi_table.select_1(&side.as_("light").as_("is_light"));
+    assert_sql!(query, "SELECT side AS is_light FROM jedi;");
+}
@@ -41,4 +41,34 @@ fn delete_returning() {
     let query: DeleteQuery<(String), LimitMany, ()> = jedi_table.delete().all().returning_1(&name);
     assert_sql!(query, "DELETE FROM jedi RETURNING name;")
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn delete_order_limit() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let query = jedi_table.delete().where_(side.is(false)).order_by(&id).limit(1000);
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "DELETE FROM jedi WHERE side = ? ORDER BY id ASC LIMIT 1000;".to_string()
+    );
+
+    assert_sql!(query, "DELETE FROM jedi WHERE jedi.ctid IN (SELECT jedi.ctid FROM jedi WHERE side = $1 ORDER BY id ASC LIMIT 1000);");
+
+    let orders_table = TableDef::new("orders");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &orders_table);
+    let total = NamedField::<i32>::field_of("total", &orders_table);
+
+    let query: DeleteQuery<i32, LimitMany, ()> = jedi_table.delete().using(&orders_table)
+        .where_(id.qual().is(jedi_id.qual()))
+        .order_by(&id).limit(10)
+        .returning_1(&total.qual());
+
+    assert_sql!(query, "DELETE FROM jedi USING orders WHERE (jedi.ctid, orders.ctid) IN (SELECT jedi.ctid, orders.ctid FROM jedi, orders WHERE jedi.id = orders.jedi_id ORDER BY id ASC LIMIT 10) RETURNING orders.total;");
+
+    // MySQL has no ORDER BY and LIMIT in multi-table statements
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+}
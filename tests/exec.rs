@@ -0,0 +1,358 @@
+use deuterium::*;
+
+#[test]
+fn cardinality() {
+
+    assert_eq!(<LimitMany as Cardinality<i32>>::collect(vec![1, 2], 2), vec![1, 2]);
+    assert_eq!(<LimitOne as Cardinality<i32>>::collect(vec![1], 1), Some(1));
+    assert_eq!(<LimitOne as Cardinality<i32>>::collect(vec![], 0), None);
+    assert_eq!(<NoResult as Cardinality<i32>>::collect(vec![], 3), 3);
+
+    assert!(<LimitMany as Cardinality<i32>>::returns_rows());
+    assert!(!<NoResult as Cardinality<i32>>::returns_rows());
+}
+
+#[test]
+fn insert_cardinality() {
+
+    // Insert without RETURNING yields affected rows count
+    assert_eq!(<() as Cardinality<()>>::collect(vec![], 2), 2);
+    assert!(!<() as Cardinality<()>>::returns_rows());
+}
+
+#[test]
+fn held_values() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let query = jedi_table.select_all().where_(name.is("Luke".to_string())).and(master_id.is(1i32));
+    let mut ctx = SqlContext::new(Box::new(sql::SqliteAdapter));
+
+    assert_eq!(query.to_final_sql(&mut ctx), "SELECT * FROM jedi WHERE (name = ?1) AND (master_id = ?2);");
+    assert_eq!(ctx.values(), &[Value::String("Luke".to_string()), Value::I32(1)]);
+    assert!(!ctx.has_opaque_values());
+
+    // Values held without their driver independent form are reported as NULL
+    #[allow(deprecated)]
+    let placeholder = ctx.hold(Box::new(2i32));
+    assert_eq!(placeholder, "$$2");
+    assert_eq!(ctx.values()[2], Value::Null);
+    assert!(ctx.has_opaque_values());
+
+    ctx.reset();
+    assert!(!ctx.has_opaque_values());
+}
+
+#[test]
+fn mock_executor() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let executor = MockExecutor::new()
+        .on("SELECT name", vec![
+            MemoryRow::new(&["name"], vec![Value::String("Luke".to_string())]),
+            MemoryRow::new(&["name"], vec![Value::String("Leia".to_string())])
+        ])
+        .on_execute("DELETE", 2);
+
+    let names = jedi_table.select_1(&name).where_(id.gt(1i32)).query(&executor).unwrap();
+    assert_eq!(names, vec![("Luke".to_string(),), ("Leia".to_string(),)]);
+
+    let name = jedi_table.select_1(&name).first().query(&executor).unwrap();
+    assert_eq!(name, Some(("Luke".to_string(),)));
+
+    let deleted = jedi_table.delete().where_(id.is(1i32)).query(&executor).unwrap();
+    assert_eq!(deleted, 2);
+
+    executor.assert_count(3);
+    executor.assert_sql(0, "SELECT name FROM jedi WHERE id > $1;");
+    executor.assert_values(0, &[Value::I32(1)]);
+    executor.assert_sql(2, "DELETE FROM jedi WHERE id = $1;");
+}
+
+#[test]
+fn mock_executor_limit() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+
+    let executor = MockExecutor::new()
+        .on("SELECT id", vec![
+            MemoryRow::new(&["id"], vec![Value::I32(1)]),
+            MemoryRow::new(&["id"], vec![Value::I32(2)]),
+            MemoryRow::new(&["id"], vec![Value::I32(3)])
+        ]);
+
+    // LIMIT doesn't turn the query into a single row one
+    let ids = jedi_table.select_1(&id).limit(10).query(&executor).unwrap();
+    assert_eq!(ids, vec![(1,), (2,), (3,)]);
+    executor.assert_sql(0, "SELECT id FROM jedi LIMIT 10;");
+}
+
+#[test]
+fn mock_executor_returning() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let executor = MockExecutor::with_adapter(sql::SqliteAdapter)
+        .on("RETURNING id", vec![MemoryRow::new(&["id"], vec![Value::I64(7)])]);
+
+    let mut query = jedi_table.insert_fields(&[&name]);
+    query.push_untyped(&["Luke".to_string().as_expr()]);
+
+    let ids: Vec<i32> = query.returning_1(&id).query(&executor).unwrap();
+    assert_eq!(ids, vec![7]);
+    assert_eq!(executor.statements()[0].values, vec![Value::String("Luke".to_string())]);
+}
+
+#[test]
+fn mock_executor_returning_decoding() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    // Rows as a backend reports them: SQLite widens integers, NULLs stay untyped
+    let executor = MockExecutor::with_adapter(sql::SqliteAdapter)
+        .on("UPDATE", vec![
+            MemoryRow::new(&["id", "master_id"], vec![Value::I64(1), Value::Null]),
+            MemoryRow::new(&["id", "master_id"], vec![Value::I64(2), Value::I64(1)])
+        ])
+        .on("DELETE", vec![MemoryRow::new(&["force_level"], vec![Value::I64(1000)])]);
+
+    let query = jedi_table.update().field(force_level.set(&10i8)).where_(id.lt(3i32)).returning_2(&id, &master_id);
+    assert_eq!(query.query(&executor).unwrap(), vec![(1, None), (2, Some(1))]);
+
+    // Values out of range of the returned type are decoding errors, not truncated
+    let query = jedi_table.delete().where_(name.is("Anakin".to_string())).returning_1(&force_level);
+    match query.query(&executor) {
+        Err(ExecError::Decode(RowError::TypeMismatch(0, "i8", "i64"))) => (),
+        other => panic!("Expected a decoding error, got {:?}", other)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_decoding() {
+
+    let conn = ::rusqlite::Connection::open_in_memory().unwrap();
+    conn.execute_batch("CREATE TABLE jedi (id INTEGER PRIMARY KEY, name TEXT NOT NULL, side BOOLEAN NOT NULL, \
+                        force_level REAL NOT NULL, master_id INTEGER);").unwrap();
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<f64>::field_of("force_level", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let mut query = jedi_table.insert_fields(&[&name, &side, &force_level]);
+    query.push_untyped(&["Luke".to_string().as_expr(), true.as_expr(), 9.5f64.as_expr()]);
+    let ids: Vec<i32> = query.returning_1(&id).query(&conn).unwrap();
+    assert_eq!(ids, vec![1]);
+
+    let rows = jedi_table.select_2(&name, &side).query(&conn).unwrap();
+    assert_eq!(rows, vec![("Luke".to_string(), true)]);
+
+    let rows = jedi_table.select_2(&force_level, &master_id).query(&conn).unwrap();
+    assert_eq!(rows, vec![(9.5, None)]);
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+#[ignore]
+fn postgres_decoding() {
+
+    // Needs a disposable database, e.g. DEUTERIUM_TEST_DATABASE=postgres://postgres@localhost/deuterium_test
+    let url = ::std::env::var("DEUTERIUM_TEST_DATABASE").unwrap();
+    let conn = ::postgres::Connection::connect(&url[..], ::postgres::TlsMode::None).unwrap();
+    conn.batch_execute("CREATE TEMPORARY TABLE jedi (id SERIAL PRIMARY KEY, name VARCHAR(64) NOT NULL, \
+                        side BOOLEAN NOT NULL, force_level SMALLINT NOT NULL, master_id INTEGER);").unwrap();
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i16>::field_of("force_level", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let mut query = jedi_table.insert_fields(&[&name, &side, &force_level]);
+    query.push_untyped(&["Luke".to_string().as_expr(), true.as_expr(), 9i16.as_expr()]);
+    let ids: Vec<(i32, String)> = query.returning_2(&id, &name).query(&conn).unwrap();
+    assert_eq!(ids, vec![(1, "Luke".to_string())]);
+
+    let rows = jedi_table.select_2(&name, &side).query(&conn).unwrap();
+    assert_eq!(rows, vec![("Luke".to_string(), true)]);
+
+    let rows = jedi_table.select_2(&force_level, &master_id).query(&conn).unwrap();
+    assert_eq!(rows, vec![(9, None)]);
+
+    // SMALLINT is decoded into narrower integers if the value fits
+    let levels: Vec<(i8,)> = jedi_table.select_1(&NamedField::<i8>::field_of("force_level", &jedi_table)).query(&conn).unwrap();
+    assert_eq!(levels, vec![(9,)]);
+
+    // AVG and SUM of integers are NUMERIC
+    let rows = jedi_table.select_2(&force_level.avg(), &force_level.sum()).query(&conn).unwrap();
+    assert_eq!(rows, vec![(9, 9)]);
+
+    let row: Option<(String, String, String)> = query_first(&conn, "SELECT -1234.05600::NUMERIC(10, 5), 0.0001::NUMERIC, 100000000::NUMERIC;");
+    assert_eq!(row, Some(("-1234.05600".to_string(), "0.0001".to_string(), "100000000".to_string())));
+
+    let rows: Vec<(Vec<i16>, Vec<Option<String>>)> = exec::query_sql::<(Vec<i16>, Vec<Option<String>>), LimitMany>(
+        &conn, "SELECT ARRAY_AGG(force_level), ARRAY['a', NULL]::TEXT[] FROM jedi;", &conn.context()).unwrap();
+    assert_eq!(rows, vec![(vec![9], vec![Some("a".to_string()), None])]);
+
+    // Decoded arrays can be bound again
+    let mut ctx = conn.context();
+    ctx.bind(Value::Array(vec![Value::I32(1), Value::Null]));
+    let row = exec::query_sql::<(Vec<Option<i32>>,), LimitOne>(&conn, "SELECT $1::INT4[];", &ctx).unwrap();
+    assert_eq!(row, Some((vec![Some(1), None],)));
+
+    // NULL bound to a typed placeholder has its type
+    let template = PreparedTemplate::compile(&jedi_table.select_1(&name).where_(side.is(Placeholder::typed::<bool>(1))), &conn).unwrap();
+    assert_eq!(template.query(&conn, &[&None::<bool>]).unwrap(), vec![]);
+
+    // Code written against PostgresQuery keeps working with generic connections
+    let generic: &::postgres::GenericConnection = &conn;
+    assert_eq!(deprecated_query(&jedi_table.select_1(&name), generic), vec![("Luke".to_string(),)]);
+}
+
+#[cfg(feature = "postgres")]
+#[allow(deprecated)]
+fn deprecated_query<Q: PostgresQuery<(String,), LimitMany>>(query: &Q, conn: &::postgres::GenericConnection) -> Vec<(String,)> {
+    query.query(conn).unwrap()
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+#[ignore]
+fn postgres_cached_connection() {
+
+    let url = ::std::env::var("DEUTERIUM_TEST_DATABASE").unwrap();
+    let conn = ::postgres::Connection::connect(&url[..], ::postgres::TlsMode::None).unwrap();
+    let other = ::postgres::Connection::connect(&url[..], ::postgres::TlsMode::None).unwrap();
+    other.batch_execute("DROP TABLE IF EXISTS cached_jedi; \
+                         CREATE TABLE cached_jedi (name VARCHAR(64) NOT NULL); \
+                         INSERT INTO cached_jedi VALUES ('Luke'), ('Anakin');").unwrap();
+
+    let jedi_table = TableDef::new("cached_jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let cached = CachedConnection::new(&conn, 4);
+
+    // Queries may run while rows of another one are decoded
+    let query = jedi_table.select_1(&name).order_by(&name);
+    let mut pairs = vec![];
+    let mut ctx = cached.context();
+    let sql = query.try_to_final_sql(&mut ctx).unwrap();
+    cached.query_rows(&sql, &ctx, &mut |row| {
+        let (jedi,): (String,) = try!(FromRow::from_row(row));
+        let names = try!(jedi_table.select_1(&name).where_(name.is(jedi.clone())).query(&cached));
+        pairs.push((jedi, names.len()));
+        Ok(())
+    }).unwrap();
+    assert_eq!(pairs, vec![("Anakin".to_string(), 1), ("Luke".to_string(), 1)]);
+    assert_eq!(cached.get_misses(), 2);
+
+    // Result columns changed by another connection
+    other.batch_execute("ALTER TABLE cached_jedi ALTER COLUMN name TYPE TEXT;").unwrap();
+    assert_eq!(query.query(&cached).unwrap(), vec![("Anakin".to_string(),), ("Luke".to_string(),)]);
+    assert_eq!(cached.get_misses(), 3);
+    other.batch_execute("DROP TABLE cached_jedi;").unwrap();
+}
+
+#[cfg(feature = "postgres")]
+fn query_first<T: FromRow>(conn: &::postgres::Connection, sql: &str) -> Option<T> {
+    exec::query_sql::<T, LimitOne>(conn, sql, &conn.context()).unwrap()
+}
+
+#[test]
+#[should_panic]
+fn mock_executor_assertion() {
+    let executor = MockExecutor::new();
+    executor.assert_count(1);
+}
+
+#[test]
+fn statement_cache() {
+
+    let mut cache: StatementCache<String> = StatementCache::new(2);
+    let prepare = |sql: &str| -> Result<String, ()> { Ok(format!("prepared {}", sql)) };
+
+    assert_eq!(cache.get_or_prepare("SELECT 1;", &prepare).unwrap(), "prepared SELECT 1;");
+    cache.get_or_prepare("SELECT 2;", &prepare).unwrap();
+    cache.get_or_prepare("SELECT 1;", &prepare).unwrap();
+
+    // SELECT 2 is the least recently used one
+    cache.get_or_prepare("SELECT 3;", &prepare).unwrap();
+    assert!(cache.contains("SELECT 1;"));
+    assert!(!cache.contains("SELECT 2;"));
+
+    assert_eq!(cache.get_hits(), 1);
+    assert_eq!(cache.get_misses(), 3);
+    assert!(cache.get_or_prepare("SELECT 4;", |_| Err(())).is_err());
+
+    cache.invalidate();
+    assert_eq!(cache.len(), 0);
+
+    // Nothing is cached without capacity
+    let mut cache: StatementCache<String> = StatementCache::new(0);
+    assert_eq!(cache.get_or_prepare("SELECT 1;", &prepare).unwrap(), "prepared SELECT 1;");
+    cache.get_or_prepare("SELECT 1;", &prepare).unwrap();
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.get_misses(), 2);
+}
+
+#[test]
+fn schema_change() {
+    assert!(exec::cache::is_schema_change("ALTER TABLE jedi ADD COLUMN side BOOLEAN;"));
+    assert!(exec::cache::is_schema_change("SELECT 1; drop table jedi;"));
+    assert!(!exec::cache::is_schema_change("SELECT * FROM jedi WHERE name = 'DROP ';"));
+    assert!(exec::cache::is_schema_change("-- Jedi are no more\nDROP TABLE jedi;"));
+    assert!(exec::cache::is_schema_change("/* ; */ create index jedi_name on jedi (name);"));
+    assert!(exec::cache::is_schema_change("WITH t AS (SELECT 1) SELECT 1; ALTER TABLE jedi DROP COLUMN side;"));
+    assert!(!exec::cache::is_schema_change("SELECT created, \"drop\" FROM jedi -- DROP\n;"));
+}
+
+#[test]
+fn prepared_template() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<String>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let executor = MockExecutor::new()
+        .on("SELECT name", vec![MemoryRow::new(&["name"], vec![Value::String("Luke".to_string())])]);
+
+    let query = jedi_table.select_1(&name)
+        .where_(name.like(Placeholder::typed::<String>(1)))
+        .and(side.is(Placeholder::new(2)))
+        .and(force_level.lt(10i8));
+
+    let template = PreparedTemplate::compile(&query, &executor).unwrap();
+    assert_eq!(template.get_sql(), "SELECT name FROM jedi WHERE ((name LIKE $1) AND (side = $2)) AND (force_level < $3);");
+    assert_eq!(template.get_slots(), &[Some("String"), None]);
+
+    let names = template.query(&executor, &[&"L%".to_string(), &"light".to_string()]).unwrap();
+    assert_eq!(names, vec![("Luke".to_string(),)]);
+    template.query(&executor, &[&"J%".to_string(), &None::<String>]).unwrap();
+
+    executor.assert_count(2);
+    executor.assert_sql(1, template.get_sql());
+    executor.assert_values(0, &[Value::String("L%".to_string()), Value::String("light".to_string()), Value::I8(10)]);
+    executor.assert_values(1, &[Value::String("J%".to_string()), Value::Null, Value::I8(10)]);
+
+    assert!(template.bind(&[&None::<String>, &5i32]).is_ok());
+    assert_eq!(template.bind(&[&"L%".to_string()]).unwrap_err(),
+        SqlError::InvalidBind("expected 2 values, got 1".to_string()));
+    assert_eq!(template.bind(&[&5i32, &"light".to_string()]).unwrap_err(),
+        SqlError::InvalidBind("placeholder 1 expects String, got i32".to_string()));
+}
@@ -29,4 +29,138 @@ fn select_with_agg() {
 
     let query = jedi_table.select_2(&name, &force_level.sum()).group_by(&[&name]);
     assert_sql!(query, "SELECT name, SUM(force_level) FROM jedi GROUP BY name;");
-}
\ No newline at end of file
+}
+
+#[test]
+fn select_group_by_grouping_sets() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let query = jedi_table.select_2(&side, &force_level.sum()).group_by_rollup(&[&side, &name]);
+    assert_sql!(query, "SELECT side, SUM(force_level) FROM jedi GROUP BY ROLLUP (side, name);");
+
+    let query = jedi_table.select_2(&side, &Grouping::new(&[&side, &name])).group_by(&[&force_level]).group_by_cube(&[&side, &name]);
+    assert_sql!(query, "SELECT side, GROUPING(side, name) FROM jedi GROUP BY force_level, CUBE (side, name);");
+
+    let query = jedi_table.select_all().group_by_grouping_sets(&[&[&side, &name], &[&side], &[]]);
+    assert_sql!(query, "SELECT * FROM jedi GROUP BY GROUPING SETS ((side, name), (side), ());");
+
+    // Plain expressions set after grouping elements keep them
+    let query = jedi_table.select_all().group_by_cube(&[&side, &name]).group_by(&[&force_level]);
+    assert_sql!(query, "SELECT * FROM jedi GROUP BY force_level, CUBE (side, name);");
+}
+
+#[test]
+fn select_group_by_rollup_mysql() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let query = jedi_table.select_all().group_by_rollup(&[&side, &name]);
+    assert_eq!(
+        query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("SELECT * FROM jedi GROUP BY side, name WITH ROLLUP;".to_string())
+    );
+
+    let query = jedi_table.select_all().group_by_cube(&[&side, &name]);
+    let mut ctx = SqlContext::new(Box::new(sql::MysqlAdapter));
+    assert!(query.try_to_final_sql(&mut ctx).is_err());
+
+    // Unchecked rendering doesn't fail and keeps the grouping, errors don't leak into the next query of the context
+    assert_eq!(query.to_final_sql(&mut ctx), "SELECT * FROM jedi GROUP BY CUBE (side, name);");
+    assert!(jedi_table.select_all().try_to_final_sql(&mut ctx).is_ok());
+}
+
+#[test]
+fn select_agg_modifiers() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let query = jedi_table.select_1(&name.count().distinct());
+    assert_sql!(query, "SELECT COUNT(DISTINCT name) FROM jedi;");
+
+    let query = jedi_table.select_1(&force_level.sum().filter(side.is(true)));
+    assert_sql!(query, "SELECT SUM(force_level) FILTER (WHERE side = $1) FROM jedi;");
+
+    let query = jedi_table.select_2(&CountAll, &CountAll.filter(side.is(true)));
+    assert_sql!(query, "SELECT COUNT(*), COUNT(*) FILTER (WHERE side = $1) FROM jedi;");
+
+    let query = jedi_table.select_1(&name.max().reverse_by(&force_level));
+    assert_sql!(query, "SELECT MAX(name ORDER BY force_level DESC) FROM jedi;");
+}
+
+#[test]
+fn select_agg_filter_mysql() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let query = jedi_table.select_1(&force_level.sum().filter(side.is(true))).where_(name.is("Luke".to_string()));
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "SELECT SUM(CASE WHEN side = ? THEN force_level END) FROM jedi WHERE name = ?;".to_string()
+    );
+
+    let query = jedi_table.select_1(&CountAll.filter(side.is(true)));
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "SELECT COUNT(CASE WHEN side = ? THEN 1 END) FROM jedi;".to_string()
+    );
+}
+
+#[test]
+fn select_more_aggs() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let query: SelectQuery<(bool, String), LimitMany, ()> = jedi_table.select_2(&side, &name.string_agg(", ").order_by(&name)).group_by(&[&side]);
+    assert_sql!(query, "SELECT side, STRING_AGG(name, $1 ORDER BY name ASC) FROM jedi GROUP BY side;");
+    assert_eq!(
+        query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("SELECT side, GROUP_CONCAT(name ORDER BY name ASC SEPARATOR ', ') FROM jedi GROUP BY side;".to_string())
+    );
+
+    let query = jedi_table.select_1(&name.string_agg("\\").distinct().filter(side.is(true)));
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "SELECT GROUP_CONCAT(DISTINCT CASE WHEN side = ? THEN name END SEPARATOR '\\\\') FROM jedi;"
+    );
+
+    let query: SelectQuery<(Vec<i8>,), LimitMany, ()> = jedi_table.select_1(&force_level.array_agg().distinct());
+    assert_sql!(query, "SELECT ARRAY_AGG(DISTINCT force_level) FROM jedi;");
+
+    let query: SelectQuery<(bool, bool), LimitMany, ()> = jedi_table.select_2(&side.bool_and(), &side.bool_or());
+    assert_sql!(query, "SELECT BOOL_AND(side), BOOL_OR(side) FROM jedi;");
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "SELECT MIN(side), MAX(side) FROM jedi;"
+    );
+
+    let query = jedi_table.select_2(&name.json_agg(), &json_object_agg(&name, &force_level));
+    assert_sql!(query, "SELECT JSON_AGG(name), JSON_OBJECT_AGG(name, force_level) FROM jedi;");
+
+    let query: SelectQuery<(f64, f64), LimitMany, ()> = jedi_table.select_2(&force_level.stddev(), &force_level.variance());
+    assert_sql!(query, "SELECT CAST(STDDEV(force_level) AS DOUBLE PRECISION), CAST(VARIANCE(force_level) AS DOUBLE PRECISION) FROM jedi;");
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "SELECT STDDEV(force_level), VARIANCE(force_level) FROM jedi;"
+    );
+
+    let query: SelectQuery<(f64,), LimitMany, ()> = jedi_table.select_1(&force_level.percentile_cont(0.5).filter(side.is(true)));
+    assert_sql!(query, "SELECT PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY force_level ASC) FILTER (WHERE side = $1) FROM jedi;");
+
+    let query: SelectQuery<(f64,), LimitMany, ()> = jedi_table.select_1(&force_level.percentile_cont(1.5));
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+}
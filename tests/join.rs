@@ -24,3 +24,70 @@ fn unconditioned_join() {
 
 }
 
+
+#[test]
+fn lateral_join() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+    let id = NamedField::<i32>::field_of("id", &jedi_table).qual();
+    let tags = JsonField::field_of("tags", &jedi_table).qual();
+    let master_id = NamedField::<i32>::field_of("master_id", &padawan_table).qual();
+    let padawan_name = NamedField::<String>::field_of("name", &padawan_table).qual();
+
+    let top = padawan_table.select_1(&padawan_name)
+        .where_(master_id.is(id.clone()))
+        .order_by(&padawan_name)
+        .limit(3)
+        .from_as("p");
+
+    let query = jedi_table.select_all().left_join(&top.lateral(), RawPredicate::new("true").upcast());
+    assert_sql!(query, "SELECT * FROM jedi LEFT JOIN LATERAL (SELECT padawan.name FROM padawan WHERE padawan.master_id = jedi.id ORDER BY padawan.name ASC LIMIT 3) as p ON true;");
+
+    let query = jedi_table.select_all().cross_join(&jsonb_array_elements(&tags).alias("t", &[]).lateral());
+    assert_sql!(query, "SELECT * FROM jedi CROSS JOIN LATERAL jsonb_array_elements(jedi.tags) AS t(value);");
+}
+
+#[test]
+fn using_join() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let padawan_side = NamedField::<bool>::field_of("side", &padawan_table).qual();
+
+    let query = jedi_table.select_1(&name).inner_join_using(&padawan_table, &[&side]).unwrap();
+    assert_sql!(query, "SELECT name FROM jedi INNER JOIN padawan USING (side);");
+
+    let query = jedi_table.select_all().left_join_using(&padawan_table, &[&padawan_side, &name]).unwrap().where_(padawan_side.unqual().is(true));
+    assert_sql!(query, "SELECT * FROM jedi LEFT JOIN padawan USING (side, name) WHERE side = $1;");
+
+    // Merged columns are referenced unqualified, qualified ones belong to one side
+    let query = jedi_table.select_2(&name.unqual(), &name.qual()).full_outer_join_using(&padawan_table, &[&name]).unwrap()
+        .where_(name.unqual().is("Luke".to_string()));
+    assert_sql!(query, "SELECT name, jedi.name FROM jedi FULL OUTER JOIN padawan USING (name) WHERE name = $1;");
+}
+
+#[test]
+fn using_join_invalid_fields() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let sith_name = NamedField::<String>::field_of("name", &TableDef::new("sith"));
+
+    let err = jedi_table.select_all().join_using(&padawan_table, &[&sith_name]).err();
+    assert_eq!(err, Some(UsingError::UnknownField("name".to_string())));
+
+    let err = jedi_table.select_all().join_using(&padawan_table, &[&name, &name]).err();
+    assert_eq!(err, Some(UsingError::DuplicateField("name".to_string())));
+
+    let err = jedi_table.select_all().join_using(&padawan_table, &[]).err();
+    assert_eq!(err, Some(UsingError::NoFields));
+
+    // Columns of derived tables are known, so the other side is checked too
+    let ranks = padawan_table.select_1(&NamedField::<i32>::field_of("rank", &padawan_table)).from_as("r");
+    let err = jedi_table.select_all().join_using(&ranks, &[&name]).err();
+    assert_eq!(err, Some(UsingError::UnknownField("name".to_string())));
+}
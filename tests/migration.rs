@@ -0,0 +1,224 @@
+use deuterium::*;
+
+fn pg() -> SqlContext {
+    SqlContext::new(Box::new(sql::PostgreSqlAdapter))
+}
+
+fn migrator() -> Migrator {
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    Migrator::new()
+        .migration(Migration::new(2, "index_jedi_name")
+            .up(jedi_table.index("jedi_name_idx").on(&[&name]).where_(name.is("Luke".to_string())))
+            .down_sql("DROP INDEX jedi_name_idx;"))
+        .migration(Migration::new(1, "create_jedi")
+            .up(jedi_table.schema().column(ColumnDef::of(&id).primary_key()).column(ColumnDef::of(&name)).create())
+            .down_sql("DROP TABLE jedi;"))
+}
+
+#[test]
+fn migration_plan_up() {
+
+    let migrator = migrator();
+    let create_jedi = &migrator.get_migrations()[0];
+    let checksum = create_jedi.checksum();
+
+    assert_eq!(create_jedi.get_version(), 1);
+    assert_eq!(checksum, create_jedi.checksum());
+
+    let plan = migrator.plan_up(&[], &mut pg()).unwrap();
+    assert_eq!(plan[0], "CREATE TABLE IF NOT EXISTS schema_migrations (version BIGINT PRIMARY KEY, name TEXT NOT NULL, checksum TEXT NOT NULL, applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP);");
+    assert_eq!(&plan[1..5], &[
+        "BEGIN;".to_string(),
+        "CREATE TABLE jedi (id INTEGER PRIMARY KEY, name TEXT NOT NULL);".to_string(),
+        format!("INSERT INTO schema_migrations (version, name, checksum) VALUES\n    (1, 'create_jedi', '{}');", checksum),
+        "COMMIT;".to_string(),
+    ]);
+    assert_eq!(plan[6], "CREATE INDEX jedi_name_idx ON jedi (name) WHERE name = 'Luke';");
+
+    let applied = AppliedMigration { version: 1, name: "create_jedi".to_string(), checksum: checksum };
+    let plan = migrator.plan_up(&[applied], &mut pg()).unwrap();
+    assert_eq!(plan.len(), 5);
+}
+
+#[test]
+fn migration_plan_down() {
+
+    let migrator = migrator();
+    let mut applied = vec![];
+    for migration in migrator.get_migrations().iter() {
+        applied.push(AppliedMigration {
+            version: migration.get_version(),
+            name: migration.get_name().to_string(),
+            checksum: migration.checksum()
+        });
+    }
+
+    assert_eq!(migrator.plan_down(&applied, 1, &mut pg()).unwrap(), vec![
+        "BEGIN;".to_string(),
+        "DROP INDEX jedi_name_idx;".to_string(),
+        "DELETE FROM schema_migrations WHERE version = 2;".to_string(),
+        "COMMIT;".to_string(),
+    ]);
+
+    let irreversible = migrator.clone().migration(Migration::new(3, "seed").up_sql("SELECT 1;"));
+    applied.push(AppliedMigration { version: 3, name: "seed".to_string(), checksum: irreversible.get_migrations()[2].checksum() });
+    match irreversible.plan_down(&applied, 1, &mut pg()) {
+        Err(MigrationError::Irreversible(3)) => (),
+        _ => panic!("Expected irreversible migration")
+    }
+}
+
+#[test]
+fn migration_checksum_mismatch() {
+
+    let migrator = migrator();
+    let applied = AppliedMigration { version: 1, name: "create_jedi".to_string(), checksum: "0".to_string() };
+    match migrator.plan_up(&[applied], &mut pg()) {
+        Err(MigrationError::ChecksumMismatch(1)) => (),
+        _ => panic!("Expected checksum mismatch")
+    }
+
+    let applied = AppliedMigration { version: 42, name: "unknown".to_string(), checksum: "0".to_string() };
+    match migrator.plan_up(&[applied], &mut pg()) {
+        Err(MigrationError::UnknownVersion(42)) => (),
+        _ => panic!("Expected unknown version")
+    }
+}
+
+#[test]
+fn migration_checksum_stable() {
+
+    // Hashed input is fixed, applied checksums stay valid across databases and releases
+    let seed = Migration::new(3, "seed").up_sql("SELECT 1;");
+    assert_eq!(seed.checksum(), "16dc30e7f08082b0");
+    assert!(Migration::new(3, "seed").up_sql("SELECT 2;").checksum() != seed.checksum());
+}
+
+#[test]
+fn migration_duplicate_version() {
+
+    let migrator = migrator().migration(Migration::new(1, "create_jedi_again").up_sql("SELECT 1;"));
+    assert_eq!(migrator.get_migrations().len(), 2);
+    match migrator.plan_up(&[], &mut pg()) {
+        Err(MigrationError::DuplicateVersion(1)) => (),
+        _ => panic!("Expected duplicate version")
+    }
+}
+
+#[test]
+fn migration_without_transaction() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let migrator = Migrator::new()
+        .migration(Migration::new(1, "index_jedi_name")
+            .up(jedi_table.index("jedi_name_idx").concurrently().on(&[&name]))
+            .without_transaction());
+
+    let plan = migrator.plan_up(&[], &mut pg()).unwrap();
+    assert_eq!(plan.len(), 3);
+    assert_eq!(plan[1], "CREATE INDEX CONCURRENTLY jedi_name_idx ON jedi (name);");
+    assert!(plan[2].starts_with("INSERT INTO schema_migrations"));
+}
+
+#[test]
+fn migration_context_reset() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    // Errors of a failed render don't leak into the next migration
+    let mut ctx = SqlContext::new(Box::new(sql::MysqlAdapter));
+    let concurrent = Migration::new(1, "index").up(jedi_table.index("jedi_name_idx").concurrently().on(&[&name]));
+    assert!(concurrent.up_to_sql(&mut ctx).is_err());
+
+    let plain = Migration::new(2, "index").up(jedi_table.index("jedi_name_idx").on(&[&name]));
+    assert_eq!(plain.up_to_sql(&mut ctx).unwrap(), vec!["CREATE INDEX jedi_name_idx ON jedi (name);".to_string()]);
+}
+
+#[test]
+fn migration_up_with_executor() {
+
+    let migrator = migrator();
+    let create_jedi = &migrator.get_migrations()[0];
+    let applied = MemoryRow::new(&["version", "name", "checksum"], vec![
+        Value::I64(1), Value::String("create_jedi".to_string()), Value::String(create_jedi.checksum())
+    ]);
+
+    let executor = MockExecutor::new().on("SELECT version", vec![applied.clone()]);
+    assert_eq!(migrator.up(&executor).unwrap(), vec![2]);
+    executor.assert_count(8);
+    executor.assert_sql(0, "SELECT pg_advisory_lock(hashtext('schema_migrations'));");
+    executor.assert_sql(2, "SELECT version, name, checksum FROM schema_migrations ORDER BY version;");
+    executor.assert_sql(3, "BEGIN;");
+    executor.assert_sql(4, "CREATE INDEX jedi_name_idx ON jedi (name) WHERE name = 'Luke';");
+    executor.assert_sql(6, "COMMIT;");
+    executor.assert_sql(7, "SELECT pg_advisory_unlock(hashtext('schema_migrations'));");
+
+    // The lock is released when a migration fails
+    let executor = MockExecutor::new().on("SELECT version", vec![applied.clone()]).on_error("CREATE INDEX", "42P07", 1);
+    assert!(migrator.up(&executor).is_err());
+    executor.assert_count(7);
+    executor.assert_sql(5, "ROLLBACK;");
+    executor.assert_sql(6, "SELECT pg_advisory_unlock(hashtext('schema_migrations'));");
+
+    let executor = MockExecutor::new().on("SELECT version", vec![applied]);
+    assert_eq!(migrator.down(&executor, 1).unwrap(), vec![1]);
+    executor.assert_sql(4, "DROP TABLE jedi;");
+    executor.assert_sql(5, "DELETE FROM schema_migrations WHERE version = 1;");
+}
+
+#[test]
+fn migration_up_other_databases() {
+
+    let migrator = Migrator::new().migration(Migration::new(1, "seed").up_sql("INSERT INTO jedi VALUES ('Luke');"));
+
+    let executor = MockExecutor::with_adapter(sql::MysqlAdapter);
+    assert_eq!(migrator.up(&executor).unwrap(), vec![1]);
+    executor.assert_sql(0, "SELECT GET_LOCK('schema_migrations', -1);");
+    executor.assert_sql(4, "INSERT INTO jedi VALUES ('Luke');");
+    executor.assert_sql(7, "SELECT RELEASE_LOCK('schema_migrations');");
+
+    // SQLite has no lock, a concurrent run fails on the primary key of the migrations table
+    let executor = MockExecutor::with_adapter(sql::SqliteAdapter);
+    assert_eq!(migrator.up(&executor).unwrap(), vec![1]);
+    executor.assert_count(6);
+    executor.assert_sql(3, "INSERT INTO jedi VALUES ('Luke');");
+}
+
+#[test]
+fn migration_dry_run() {
+
+    // Only the migrations table is created and read, migrations are printed
+    let executor = MockExecutor::new();
+    assert_eq!(migrator().dry_run().up(&executor).unwrap(), vec![1, 2]);
+    executor.assert_count(4);
+    executor.assert_sql(1, "CREATE TABLE IF NOT EXISTS schema_migrations (version BIGINT PRIMARY KEY, name TEXT NOT NULL, \
+                            checksum TEXT NOT NULL, applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP);");
+    executor.assert_sql(3, "SELECT pg_advisory_unlock(hashtext('schema_migrations'));");
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+#[ignore]
+fn postgres_migration() {
+
+    let url = ::std::env::var("DEUTERIUM_TEST_DATABASE").unwrap();
+    let conn = ::postgres::Connection::connect(&url[..], ::postgres::TlsMode::None).unwrap();
+    conn.batch_execute("DROP TABLE IF EXISTS migrated_jedi; DROP TABLE IF EXISTS test_migrations;").unwrap();
+
+    let migrator = Migrator::new().table("test_migrations")
+        .migration(Migration::new(1, "create_jedi")
+            .up_sql("CREATE TABLE migrated_jedi (name TEXT NOT NULL);")
+            .down_sql("DROP TABLE migrated_jedi;"));
+
+    assert_eq!(migrator.up(&conn).unwrap(), vec![1]);
+    assert_eq!(migrator.up(&conn).unwrap(), vec![]);
+    assert_eq!(migrator.applied(&conn).unwrap()[0].checksum, migrator.get_migrations()[0].checksum());
+    assert_eq!(migrator.down(&conn, 1).unwrap(), vec![1]);
+    assert!(migrator.applied(&conn).unwrap().is_empty());
+}
@@ -0,0 +1,87 @@
+use deuterium::*;
+
+#[derive(Debug, PartialEq)]
+struct Jedi {
+    id: i32,
+    name: String,
+    master_id: Option<i32>
+}
+
+from_row!(Jedi { id, name, master_id });
+
+fn luke() -> MemoryRow {
+    MemoryRow::new(&["id", "name", "master_id"], vec![Value::I32(1), Value::String("Luke".to_string()), Value::Null])
+}
+
+#[test]
+fn tuple_from_row() {
+
+    let row: (i32, String, Option<i32>) = FromRow::from_row(&luke()).unwrap();
+    assert_eq!(row, (1, "Luke".to_string(), None));
+
+    let id: i32 = FromRow::from_row(&MemoryRow::new(&["id"], vec![Value::I32(1)])).unwrap();
+    assert_eq!(id, 1);
+}
+
+#[test]
+fn struct_from_row() {
+
+    let jedi: Jedi = FromRow::from_row(&luke()).unwrap();
+    assert_eq!(jedi, Jedi { id: 1, name: "Luke".to_string(), master_id: None });
+
+    let row = MemoryRow::new(&["id", "name"], vec![Value::I32(1), Value::String("Luke".to_string())]);
+    assert_eq!(<Jedi as FromRow>::from_row(&row), Err(RowError::MissingColumn("master_id".to_string())));
+}
+
+#[test]
+fn row_errors() {
+
+    assert_eq!(<(i32, String) as FromRow>::from_row(&luke()), Err(RowError::ColumnCount(2, 3)));
+    assert_eq!(<(String, String, Option<i32>) as FromRow>::from_row(&luke()), Err(RowError::TypeMismatch(0, "String", "i32")));
+    assert_eq!(<(i32, String, i32) as FromRow>::from_row(&luke()), Err(RowError::UnexpectedNull(2)));
+}
+
+#[test]
+fn integer_from_value() {
+
+    // Integers of any width are accepted if they fit
+    assert_eq!(<i64 as FromValue>::from_value(0, &Value::I32(1)), Ok(1i64));
+    assert_eq!(<i32 as FromValue>::from_value(0, &Value::I64(1)), Ok(1i32));
+    assert_eq!(<i8 as FromValue>::from_value(0, &Value::I64(1000)), Err(RowError::TypeMismatch(0, "i8", "i64")));
+    assert_eq!(<bool as FromValue>::from_value(0, &Value::I64(1)), Ok(true));
+}
+
+#[test]
+fn numeric_and_array_from_value() {
+
+    // SMALLINT columns decode into i8 if the value fits
+    assert_eq!(<i8 as FromValue>::from_value(0, &Value::I16(9)), Ok(9i8));
+
+    let avg = Value::Numeric("9.5000000000000000".to_string());
+    assert_eq!(<f64 as FromValue>::from_value(0, &avg), Ok(9.5));
+    assert_eq!(<String as FromValue>::from_value(0, &avg), Ok("9.5000000000000000".to_string()));
+    assert_eq!(<i64 as FromValue>::from_value(0, &Value::Numeric("42".to_string())), Ok(42));
+    assert_eq!(<i16 as FromValue>::from_value(0, &Value::Numeric("-9.000".to_string())), Ok(-9));
+    assert_eq!(<i64 as FromValue>::from_value(0, &avg), Err(RowError::TypeMismatch(0, "i64", "Numeric")));
+
+    let levels = Value::Array(vec![Value::I16(1), Value::Null, Value::I16(3)]);
+    assert_eq!(<Vec<Option<i8>> as FromValue>::from_value(0, &levels), Ok(vec![Some(1), None, Some(3)]));
+    assert_eq!(<Vec<i8> as FromValue>::from_value(0, &levels), Err(RowError::UnexpectedNull(0)));
+    assert_eq!(<Vec<i8> as FromValue>::from_value(0, &Value::I8(1)), Err(RowError::TypeMismatch(0, "Array", "i8")));
+}
+
+#[test]
+fn timespec_from_value() {
+
+    let at = ::time::Timespec::new(1420070400, 123456789);
+    let text = ::time::at_utc(at).strftime(exec::row::TIMESPEC_FORMAT).unwrap().to_string();
+    assert_eq!(text, "2015-01-01T00:00:00.123456789Z");
+    assert_eq!(<::time::Timespec as FromValue>::from_value(0, &Value::String(text)), Ok(at));
+
+    // Whole seconds and SQLite's CURRENT_TIMESTAMP
+    let at = ::time::Timespec::new(1420070400, 0);
+    assert_eq!(<::time::Timespec as FromValue>::from_value(0, &Value::String("2015-01-01T00:00:00Z".to_string())), Ok(at));
+    assert_eq!(<::time::Timespec as FromValue>::from_value(0, &Value::String("2015-01-01 00:00:00".to_string())), Ok(at));
+    assert_eq!(<::time::Timespec as FromValue>::from_value(0, &Value::String("yesterday".to_string())),
+               Err(RowError::TypeMismatch(0, "Timespec", "String")));
+}
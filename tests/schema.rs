@@ -0,0 +1,248 @@
+use deuterium::*;
+
+#[test]
+fn create_table() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let schema = jedi_table.schema()
+        .column(ColumnDef::of(&id).primary_key())
+        .column(ColumnDef::of(&name).unique().default(&"Padawan's name".to_string()))
+        .column(ColumnDef::of(&side).default(&true))
+        .column(ColumnDef::of(&master_id).references(&id));
+
+    assert_sql!(schema.create(), "CREATE TABLE jedi (id INTEGER PRIMARY KEY, name TEXT NOT NULL DEFAULT 'Padawan''s name' UNIQUE, side BOOLEAN NOT NULL DEFAULT true, master_id INTEGER REFERENCES jedi (id));");
+
+    assert_eq!(
+        schema.create().if_not_exists().to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))),
+        "CREATE TABLE IF NOT EXISTS jedi (id INTEGER PRIMARY KEY, name TEXT NOT NULL DEFAULT 'Padawan''s name' UNIQUE, side INTEGER NOT NULL DEFAULT true, master_id INTEGER REFERENCES jedi (id));".to_string()
+    );
+}
+
+#[test]
+fn create_table_composite_keys() {
+
+    let membership_table = TableDef::new("membership");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &membership_table);
+    let order_id = NamedField::<i32>::field_of("order_id", &membership_table);
+    let rank = NamedField::<i16>::field_of("rank", &membership_table);
+
+    let schema = membership_table.schema()
+        .column(ColumnDef::of(&jedi_id))
+        .column(ColumnDef::of(&order_id))
+        .column(ColumnDef::of(&rank))
+        .primary_key(&[&jedi_id, &order_id])
+        .unique(&[&order_id, &rank]);
+
+    assert_eq!(
+        schema.create().to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "CREATE TABLE membership (jedi_id INT NOT NULL, order_id INT NOT NULL, rank SMALLINT NOT NULL, PRIMARY KEY (jedi_id, order_id), UNIQUE (order_id, rank));".to_string()
+    );
+}
+
+#[test]
+fn create_table_mysql_keyed_strings() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<String>::field_of("side", &jedi_table);
+    let bio = NamedField::<String>::field_of("bio", &jedi_table);
+    let code = NamedField::<String>::field_of("code", &jedi_table);
+
+    let schema = jedi_table.schema()
+        .column(ColumnDef::of(&name).primary_key())
+        .column(ColumnDef::of(&side).default(&"light".to_string()))
+        .column(ColumnDef::of(&bio))
+        .column(ColumnDef::of(&code))
+        .unique(&[&code]);
+
+    assert_sql!(schema.create(), "CREATE TABLE jedi (name TEXT PRIMARY KEY, side TEXT NOT NULL DEFAULT 'light', \
+                                  bio TEXT NOT NULL, code TEXT NOT NULL, UNIQUE (code));");
+
+    // The type is kept, MySQL needs a prefix length for them
+    assert!(schema.create().try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+
+    let schema = jedi_table.schema().column(ColumnDef::of(&bio));
+    assert_eq!(
+        schema.create().try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("CREATE TABLE jedi (bio TEXT NOT NULL);".to_string())
+    );
+}
+
+#[test]
+fn alter_table() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let rank = NamedField::<i16>::field_of("rank", &jedi_table);
+    let force_level = NamedField::<i64>::field_of("force_level", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let query = jedi_table.alter()
+        .add_column(ColumnDef::of(&rank).default(&0i16))
+        .set_type(&force_level)
+        .set_default(&rank, &1i16)
+        .drop_not_null(&master_id)
+        .rename_column(&name, "full_name")
+        .add_foreign_key("jedi_master_fk", &[&master_id], &[&id])
+        .drop_constraint("jedi_name_key");
+
+    assert_eq!(query.try_to_final_statements(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))), Ok(vec![
+        "ALTER TABLE jedi ADD COLUMN rank SMALLINT NOT NULL DEFAULT 0;".to_string(),
+        "ALTER TABLE jedi ALTER COLUMN force_level TYPE BIGINT;".to_string(),
+        "ALTER TABLE jedi ALTER COLUMN rank SET DEFAULT 1;".to_string(),
+        "ALTER TABLE jedi ALTER COLUMN master_id DROP NOT NULL;".to_string(),
+        "ALTER TABLE jedi RENAME COLUMN name TO full_name;".to_string(),
+        "ALTER TABLE jedi ADD CONSTRAINT jedi_master_fk FOREIGN KEY (master_id) REFERENCES jedi (id);".to_string(),
+        "ALTER TABLE jedi DROP CONSTRAINT jedi_name_key;".to_string()
+    ]));
+
+    // Drivers run one statement per call, so several actions can't be rendered as a single string
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+
+    let query = jedi_table.alter().set_not_null(&rank);
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN rank SET NOT NULL;");
+
+    // Actions after a rename target the new name
+    let query = jedi_table.alter().rename_to("jedis").drop_column(&rank);
+    assert_eq!(query.try_to_final_statements(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))), Ok(vec![
+        "ALTER TABLE jedi RENAME TO jedis;".to_string(),
+        "ALTER TABLE jedis DROP COLUMN rank;".to_string()
+    ]));
+
+    let query = jedi_table.alter().modify_column(ColumnDef::of(&force_level).default(&100i64));
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN force_level TYPE BIGINT, ALTER COLUMN force_level SET NOT NULL, \
+                        ALTER COLUMN force_level SET DEFAULT 100;");
+
+    let query = jedi_table.alter().add_foreign_key("jedi_master_fk", &[&master_id], &[]);
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+}
+
+#[test]
+fn alter_table_mysql() {
+
+    let jedi_table = TableDef::new("jedi");
+    let rank = NamedField::<i16>::field_of("rank", &jedi_table);
+    let force_level = NamedField::<i64>::field_of("force_level", &jedi_table);
+
+    // MODIFY COLUMN restates the whole definition, so the default is kept
+    let query = jedi_table.alter().modify_column(ColumnDef::of(&force_level).default(&100i64));
+    assert_eq!(
+        query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("ALTER TABLE jedi MODIFY COLUMN force_level BIGINT NOT NULL DEFAULT 100;".to_string())
+    );
+
+    let query = jedi_table.alter().set_not_null(&rank);
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+
+    let query = jedi_table.alter().set_type(&force_level);
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+}
+
+#[test]
+fn alter_table_sqlite() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let rank = NamedField::<Option<i16>>::field_of("rank", &jedi_table);
+
+    let query = jedi_table.alter().add_column(ColumnDef::of(&rank)).rename_column(&name, "full_name").rename_to("jedis");
+    assert_eq!(query.try_to_final_statements(&mut SqlContext::new(Box::new(sql::SqliteAdapter))), Ok(vec![
+        "ALTER TABLE jedi ADD COLUMN rank INTEGER;".to_string(),
+        "ALTER TABLE jedi RENAME COLUMN name TO full_name;".to_string(),
+        "ALTER TABLE jedi RENAME TO jedis;".to_string()
+    ]));
+
+    let query = jedi_table.alter().drop_default(&name);
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))).is_err());
+}
+
+#[test]
+fn column_default_literals() {
+
+    let jedi_table = TableDef::new("jedi");
+    let motto = NamedField::<String>::field_of("motto", &jedi_table);
+    let seal = NamedField::<Vec<u8>>::field_of("seal", &jedi_table);
+    let power = NamedField::<f64>::field_of("power", &jedi_table);
+
+    let query = jedi_table.alter().set_default(&motto, &"Don't \\ panic".to_string());
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN motto SET DEFAULT 'Don''t \\ panic';");
+    assert_eq!(
+        query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("ALTER TABLE jedi ALTER COLUMN motto SET DEFAULT 'Don''t \\\\ panic';".to_string())
+    );
+
+    let query = jedi_table.alter().set_default(&seal, &vec![0xde, 0xad]);
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN seal SET DEFAULT '\\xdead'::bytea;");
+    assert_eq!(
+        query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok("ALTER TABLE jedi ALTER COLUMN seal SET DEFAULT X'dead';".to_string())
+    );
+
+    let query = jedi_table.alter().set_default(&power, &::std::f64::NAN);
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN power SET DEFAULT 'NaN'::float8;");
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+
+    let query = jedi_table.alter().set_default(&power, &::std::f64::NEG_INFINITY);
+    assert_sql!(query, "ALTER TABLE jedi ALTER COLUMN power SET DEFAULT '-Infinity'::float8;");
+}
+
+#[test]
+fn create_index() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let index = jedi_table.index("jedi_name_idx").unique().on(&[&name, &side]);
+    assert_sql!(index, "CREATE UNIQUE INDEX jedi_name_idx ON jedi (name, side);");
+
+    let index = jedi_table.index("jedi_lower_name_idx")
+        .concurrently()
+        .if_not_exists()
+        .using(IndexMethod::Gin)
+        .on(&[&RawExpression::new("lower(name)")])
+        .include(&[&side]);
+    assert_sql!(index, "CREATE INDEX CONCURRENTLY IF NOT EXISTS jedi_lower_name_idx ON jedi USING gin ((lower(name))) INCLUDE (side);");
+
+    // Aliases are dropped, aliased expressions are indexed in full
+    let lower_name = RawExpression::new("lower(name)").as_("lower_name");
+    let index = jedi_table.index("jedi_lower_name_idx").on(&[&lower_name, &side.as_("light")]);
+    assert_sql!(index, "CREATE INDEX jedi_lower_name_idx ON jedi ((lower(name)), side);");
+
+    let index = jedi_table.index("jedi_idx");
+    assert!(index.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+}
+
+#[test]
+fn create_partial_index() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let index = jedi_table.index("jedi_light_idx").on(&[&name])
+        .where_(side.is(true))
+        .and_exclude(name.is("Anakin's".to_string()));
+
+    assert_sql!(index, "CREATE INDEX jedi_light_idx ON jedi (name) WHERE (side = true) AND (name != 'Anakin''s');");
+
+    assert_eq!(
+        index.to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))),
+        "CREATE INDEX jedi_light_idx ON jedi (name) WHERE (side = true) AND (name != 'Anakin''s');".to_string()
+    );
+
+    assert!(index.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+
+    // Nothing can be bound to DDL
+    let index = jedi_table.index("jedi_name_idx").on(&[&name]).where_(name.is(Placeholder::new(1)));
+    assert!(index.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+
+    assert!(jedi_table.index("jedi_name_idx").on(&[&name]).using(IndexMethod::Hash)
+        .try_to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))).is_err());
+}
@@ -73,3 +73,76 @@ fn select_exists() {
     assert_sql!(query, "SELECT 1 FROM jedi WHERE name = $1;");    
 }
 
+
+#[test]
+fn select_from_select_fields() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &jedi_table);
+
+    let strong = jedi_table.select_2(&name, &force_level).where_(force_level.gt(50i8)).from_as("strong");
+    let (strong_name, strong_force_level) = strong.fields().unwrap();
+
+    let query: SelectQuery<(String,), LimitMany, ()> = strong.select_1(&strong_name).order_by(&strong_force_level);
+    assert_sql!(query, "SELECT strong.name FROM (SELECT name, force_level FROM jedi WHERE force_level > $1) as strong ORDER BY strong.force_level ASC;");
+
+    let counts = jedi_table.select_2(&name, &CountAll).group_by(&[&name]).from_as("counts");
+    assert!(counts.fields().is_none());
+
+    let counts = jedi_table.select_2(&name, &CountAll.as_("total")).group_by(&[&name]).from_as("counts");
+    assert!(counts.fields().is_some());
+}
+
+#[test]
+fn select_from_function() {
+
+    let series = generate_series(&1i32, &10i32).alias("s", &["n"]);
+    let (n,) = series.fields();
+
+    let query: SelectQuery<(i32,), LimitMany, ()> = series.select_1(&n).where_(n.gt(5i32));
+    assert_sql!(query, "SELECT s.n FROM generate_series($1, $2) AS s(n) WHERE s.n > $3;");
+
+    let ids = unnest::<i32>(&vec![1i32, 2i32]).alias("u", &["id"]);
+    let query = ids.select_all();
+    assert_sql!(query, "SELECT * FROM unnest(ARRAY[$1, $2]) AS u(id);");
+
+    // Default names are kept when the aliases don't match the columns
+    let jedi_table = TableDef::new("jedi");
+    let skills = JsonField::field_of("skills", &jedi_table);
+    let pairs = jsonb_each(&skills).alias("p", &["k"]);
+    let (key, value) = pairs.fields();
+    let query = pairs.select_2(&key, &value);
+    assert_eq!(query.to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))),
+               "SELECT p.key, p.value FROM jsonb_each(skills) AS p(key, value);".to_string());
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+}
+
+#[test]
+fn select_for_locking_clauses() {
+
+    let jobs_table = TableDef::new("jobs");
+    let jedi_table = TableDef::new("jedi").alias("j");
+    let done = NamedField::<bool>::field_of("done", &jobs_table);
+
+    let query = jobs_table.select_all().where_(done.is(false)).limit(10).for_update_skip_locked();
+    assert_sql!(query, "SELECT * FROM jobs WHERE done = $1 LIMIT 10 FOR UPDATE SKIP LOCKED;");
+
+    let query = jobs_table.select_all().cross_join(&jedi_table)
+        .lock(SelectFor::no_key_update().of(&[&jobs_table]).nowait())
+        .add_lock(SelectFor::key_share().of(&[&jedi_table]));
+    assert_sql!(query, "SELECT * FROM jobs CROSS JOIN jedi AS j FOR NO KEY UPDATE OF jobs NOWAIT FOR KEY SHARE OF j;");
+
+    // Shortcuts replace the previous clause
+    let query = jobs_table.select_all().for_update().for_share_nowait();
+    assert_sql!(query, "SELECT * FROM jobs FOR SHARE NOWAIT;");
+
+    let query = jobs_table.select_all().for_key_share();
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+
+    // SQLite has no row locks, every clause is reported
+    let query = jobs_table.select_all().for_update_skip_locked().add_lock(SelectFor::share());
+    let mut ctx = SqlContext::new(Box::new(sql::SqliteAdapter));
+    assert!(query.try_to_final_sql(&mut ctx).is_err());
+    assert_eq!(ctx.get_errors().len(), 2);
+}
@@ -0,0 +1,60 @@
+use deuterium::*;
+
+table! {
+    jedi {
+        id: i32,
+        name: String,
+        master_id: Option<i32>,
+    }
+
+    padawan {
+        id: i32,
+        jedi_id: i32
+    }
+
+    holocron {
+        id: i32,
+        table: String,
+        schema: String,
+        alias: Option<String>
+    }
+}
+
+#[test]
+fn table_macro() {
+
+    assert_eq!(jedi::NAME, "jedi");
+    assert_eq!(jedi::COLUMNS, &["id", "name", "master_id"]);
+
+    let query = jedi::table().select_1(&jedi::name()).where_(jedi::id().is(1i32));
+    assert_sql!(query, "SELECT name FROM jedi WHERE id = $1;");
+
+    assert_sql!(jedi::schema().create(), "CREATE TABLE jedi (id INTEGER NOT NULL, name TEXT NOT NULL, master_id INTEGER);");
+}
+
+#[test]
+fn table_macro_alias() {
+
+    let j = jedi::alias("j");
+    let p = padawan::alias("p");
+
+    let query = j.table().select_2(&j.name(), &p.id())
+        .inner_join(p.table(), p.jedi_id().is(j.id()));
+
+    assert_sql!(query, "SELECT j.name, p.id FROM jedi AS j INNER JOIN padawan AS p ON p.jedi_id = j.id;");
+}
+
+#[test]
+fn table_macro_reserved_columns() {
+
+    // Columns named like table helpers are shadowed by them and stay reachable through `columns`
+    assert_eq!(holocron::COLUMNS, &["id", "table", "schema", "alias"]);
+
+    let query = holocron::table().select_2(&holocron::id(), &holocron::columns::table())
+        .where_(holocron::columns::schema().is("jedi".to_string()));
+    assert_sql!(query, "SELECT id, table FROM holocron WHERE schema = $1;");
+
+    let h = holocron::alias("h");
+    let query = h.table().select_2(&h.columns().table(), &h.alias());
+    assert_sql!(query, "SELECT h.table, h.alias FROM holocron AS h;");
+}
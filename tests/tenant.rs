@@ -0,0 +1,217 @@
+use deuterium::*;
+
+#[test]
+fn scope_select() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let padawan_table = TableDef::new("padawan").alias("p");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &padawan_table);
+
+    let planet_table = TableDef::new("planet");
+
+    let scope = TenantScope::new(&["jedi", "padawan"], 7i32);
+
+    let query = jedi_table.select_1(&name);
+    assert_sql!(scope.scope_select(&query).unwrap(), "SELECT name FROM jedi WHERE jedi.tenant_id = $1;");
+
+    let query = jedi_table.select_1(&name)
+        .inner_join(&padawan_table, jedi_id.is(id.clone()))
+        .where_(name.is("Luke".to_string()));
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT name FROM jedi INNER JOIN padawan AS p ON (p.jedi_id = id) AND (p.tenant_id = $1) WHERE (name = $2) AND (jedi.tenant_id = $3);");
+
+    // Tables that are not scoped are left alone, inner joins without ON are filtered in WHERE
+    let query = jedi_table.select_1(&name).cross_join(&planet_table).cross_join(&padawan_table);
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT name FROM jedi CROSS JOIN planet CROSS JOIN padawan AS p WHERE (jedi.tenant_id = $1) AND (p.tenant_id = $2);");
+
+    let query = jedi_table.select_1(&name).natural_left_join(&padawan_table);
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::Unscopable("padawan".to_string())));
+}
+
+#[test]
+fn scope_outer_join() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table).qual();
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let planet_table = TableDef::new("planet");
+    let planet_id = NamedField::<i32>::field_of("id", &planet_table).qual();
+
+    let scope = TenantScope::new(&["jedi"], 7i32);
+
+    // The right side of RIGHT JOIN keeps its rows whatever ON says
+    let query = planet_table.select_all().right_join(&jedi_table, planet_id.is(id.clone()));
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT * FROM planet RIGHT JOIN jedi ON planet.id = jedi.id WHERE jedi.tenant_id = $1;");
+
+    // and the left side loses the rows without a match only in ON
+    let query = jedi_table.select_1(&name).right_outer_join(&planet_table, planet_id.is(id.clone()));
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT name FROM jedi RIGHT OUTER JOIN planet ON (planet.id = jedi.id) AND (jedi.tenant_id = $1);");
+
+    let query = jedi_table.select_1(&name).natural_right_join(&planet_table);
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::Unscopable("jedi".to_string())));
+
+    let query = planet_table.select_all().full_join(&jedi_table, planet_id.is(id.clone()));
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::Unscopable("jedi".to_string())));
+
+    let query = jedi_table.select_1(&name).full_outer_join(&planet_table, planet_id.is(id.clone()));
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::Unscopable("jedi".to_string())));
+}
+
+#[test]
+fn scope_conflict() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let tenant_id = NamedField::<i32>::field_of("tenant_id", &jedi_table);
+
+    let scope = TenantScope::new(&["jedi"], 7i32);
+
+    let query = jedi_table.select_1(&name).where_(tenant_id.is(7i32));
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT name FROM jedi WHERE (tenant_id = $1) AND (jedi.tenant_id = $2);");
+
+    let query = jedi_table.select_1(&name).where_(name.is("Luke".to_string())).or(tenant_id.is(8i32));
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+
+    // Excluding another tenant doesn't conflict
+    let query = jedi_table.select_1(&name).exclude(tenant_id.is(8i32));
+    assert!(scope.scope_select(&query).is_ok());
+
+    let query = jedi_table.update().field(tenant_id.set(&8i32)).all();
+    assert_eq!(scope.scope_update(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+}
+
+#[test]
+fn scope_modify() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let scope = TenantScope::new(&["jedi"], 7i32);
+
+    let query = jedi_table.update().field(name.set(&"Vader".to_string())).where_(name.is("Anakin".to_string()));
+    assert_sql!(scope.scope_update(&query).unwrap(),
+        "UPDATE jedi SET name = $1 WHERE (name = $2) AND (jedi.tenant_id = $3);");
+
+    // Query without WHERE doesn't touch any row and stays that way
+    let query = jedi_table.update().field(name.set(&"Vader".to_string()));
+    assert_sql!(scope.scope_update(&query).unwrap(), "UPDATE jedi SET name = $1 WHERE true = false;");
+
+    let query = jedi_table.delete().all();
+    assert_sql!(scope.scope_delete(&query).unwrap(), "DELETE FROM jedi WHERE jedi.tenant_id = $1;");
+}
+
+#[test]
+fn scope_insert() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let tenant_id = NamedField::<i32>::field_of("tenant_id", &jedi_table);
+
+    let scope = TenantScope::new(&["jedi"], 7i32);
+
+    assert_sql!(scope.scope_insert(&jedi_table.insert_all()).unwrap(),
+        "INSERT INTO jedi (tenant_id) VALUES\n    ($1);");
+
+    let mut query = jedi_table.insert_fields(&[&name]);
+    query.push_untyped(&["Luke".to_string().as_expr()]);
+    assert_sql!(scope.scope_insert(&query).unwrap(),
+        "INSERT INTO jedi (name, tenant_id) VALUES\n    ($1, $2);");
+
+    let mut query = jedi_table.insert_fields(&[&name, &tenant_id]);
+    query.push_untyped(&["Luke".to_string().as_expr(), 7i32.as_expr()]);
+    assert_sql!(scope.scope_insert(&query).unwrap(),
+        "INSERT INTO jedi (name, tenant_id) VALUES\n    ($1, $2);");
+
+    query.push_untyped(&["Leia".to_string().as_expr(), 8i32.as_expr()]);
+    assert_eq!(scope.scope_insert(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+}
+
+#[test]
+fn scope_nested() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let tenant_id = NamedField::<i32>::field_of("tenant_id", &jedi_table);
+
+    let padawan_table = TableDef::new("padawan");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &padawan_table);
+    let padawan_tenant_id = NamedField::<i32>::field_of("tenant_id", &padawan_table);
+
+    let scope = TenantScope::new(&["jedi", "padawan"], 7i32);
+
+    let query = jedi_table.select_1(&name).where_(id.in_(padawan_table.select_1(&jedi_id)));
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT name FROM jedi WHERE (id IN (SELECT jedi_id FROM padawan WHERE padawan.tenant_id = $1)) AND (jedi.tenant_id = $2);");
+
+    let query = jedi_table.select_all().from_as("j").select_all();
+    assert_sql!(scope.scope_select(&query).unwrap(),
+        "SELECT * FROM (SELECT * FROM jedi WHERE jedi.tenant_id = $1) as j;");
+
+    let query = jedi_table.select_1(&name).where_(id.in_(padawan_table.select_1(&jedi_id).where_(padawan_tenant_id.is(8i32))));
+    assert_eq!(scope.scope_select(&query).err(), Some(TenantError::ConflictingTenant("padawan".to_string())));
+
+    let query = jedi_table.update().field(name.set(&"Vader".to_string()))
+        .where_(id.in_(padawan_table.select_1(&jedi_id)));
+    assert_sql!(scope.scope_update(&query).unwrap(),
+        "UPDATE jedi SET name = $1 WHERE (id IN (SELECT jedi_id FROM padawan WHERE padawan.tenant_id = $2)) AND (jedi.tenant_id = $3);");
+
+    let query = jedi_table.delete().where_(RawPredicate::new("id IN (SELECT jedi_id FROM padawan)").upcast());
+    assert_eq!(scope.scope_delete(&query).err(), Some(TenantError::Unscopable("padawan".to_string())));
+
+    let query = jedi_table.select_1(&name).where_(tenant_id.is(7i32));
+    assert!(scope.scope_select(&query).is_ok());
+}
+
+#[test]
+fn scope_assigned() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let tenant_id = NamedField::<i32>::field_of("tenant_id", &jedi_table);
+    let master_id = NamedField::<i32>::field_of("master_id", &jedi_table);
+
+    let scope = TenantScope::new(&["jedi"], 7i32);
+
+    let query = jedi_table.update().field(tenant_id.set(&7i32)).all();
+    assert_sql!(scope.scope_update(&query).unwrap(), "UPDATE jedi SET tenant_id = $1 WHERE jedi.tenant_id = $2;");
+
+    let org = NamedField::<String>::field_of("org", &jedi_table);
+    let org_scope = TenantScope::new(&["jedi"], "yavin".to_string()).column("org");
+    let query = jedi_table.update().field(org.set(&Placeholder::new(1))).all();
+    assert_eq!(org_scope.scope_update(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+
+    let query = jedi_table.update().field(tenant_id.set(&master_id)).all();
+    assert_eq!(scope.scope_update(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+
+    // Other columns may be set to the tenant column
+    let query = jedi_table.update().field(master_id.set(&tenant_id)).all();
+    assert!(scope.scope_update(&query).is_ok());
+
+    let mut query = jedi_table.insert_fields(&[&name, &tenant_id]);
+    query.push_untyped(&["Luke".to_string().as_expr(), Placeholder::new(1).as_expr()]);
+    assert_eq!(scope.scope_insert(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+
+    let mut query: InsertQuery<(String, i32), (InsertValue<String>, InsertValue<i32>), (), (), ()> =
+        InsertQuery::new_with_cols(&jedi_table, vec![name.upcast_field(), tenant_id.upcast_field()]);
+    query.push((InsertValue::new("Luke".to_string().as_expr()), InsertValue::new(7i32.as_expr())));
+    query.push((InsertValue::new("Leia".to_string().as_expr()), InsertValue::Default));
+    assert_sql!(scope.scope_insert(&query).unwrap(),
+        "INSERT INTO jedi (name, tenant_id) VALUES\n    ($1, $2),\n    ($3, $4);");
+
+    query.push((InsertValue::new("Han".to_string().as_expr()), InsertValue::new(8i32.as_expr())));
+    assert_eq!(scope.scope_insert(&query).err(), Some(TenantError::ConflictingTenant("jedi".to_string())));
+
+    let mut query: InsertQuery<(String,), (InsertValue<String>,), (), (), ()> =
+        InsertQuery::new_with_cols(&jedi_table, vec![name.upcast_field()]);
+    query.push((InsertValue::new("Luke".to_string().as_expr()),));
+    assert_eq!(scope.scope_insert(&query).err(), Some(TenantError::Unscopable("jedi".to_string())));
+}
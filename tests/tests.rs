@@ -1,5 +1,10 @@
+#[macro_use]
 extern crate deuterium;
 extern crate time;
+#[cfg(feature = "postgres")]
+extern crate postgres;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
 
 use deuterium::*;
 
@@ -19,6 +24,16 @@ mod insert;
 mod update;
 mod delete;
 mod placeholder;
+mod alias;
+mod truncate;
+mod schema;
+mod migration;
+mod table_macro;
+mod exec;
+mod row;
+mod transaction;
+mod visitor;
+mod tenant;
 
 #[test]
 fn select_order() {
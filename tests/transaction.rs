@@ -0,0 +1,119 @@
+use deuterium::*;
+
+#[test]
+fn begin_transaction() {
+
+    assert_sql!(TransactionOptions::new(), "BEGIN;");
+
+    let options = TransactionOptions::new().isolation(IsolationLevel::Serializable).read_only().deferrable();
+    assert_sql!(options, "BEGIN ISOLATION LEVEL SERIALIZABLE, READ ONLY, DEFERRABLE;");
+
+    let options = TransactionOptions::new().isolation(IsolationLevel::RepeatableRead).read_only();
+    assert_eq!(
+        options.try_to_final_statements(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok(vec![
+            "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ;".to_string(),
+            "START TRANSACTION READ ONLY;".to_string()
+        ])
+    );
+
+    // Two statements can't be sent as one
+    assert!(options.try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+    assert_eq!(
+        TransactionOptions::new().try_to_final_statements(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok(vec!["BEGIN;".to_string()])
+    );
+
+    assert!(options.try_to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))).is_err());
+}
+
+#[test]
+fn savepoints() {
+
+    let jedi_table = TableDef::new("jedi");
+    let executor = MockExecutor::new();
+
+    {
+        let trans = Transaction::begin(&executor, &TransactionOptions::new()).unwrap();
+        jedi_table.delete().all().query(&trans).unwrap();
+
+        {
+            let nested = trans.transaction().unwrap();
+            jedi_table.delete().all().query(&nested).unwrap();
+            nested.commit().unwrap();
+        }
+
+        {
+            let _nested = trans.transaction().unwrap();
+            // Rolled back on drop
+        }
+
+        trans.commit().unwrap();
+    }
+
+    let statements: Vec<String> = executor.statements().into_iter().map(|s| s.sql).collect();
+    assert_eq!(statements, vec![
+        "BEGIN;",
+        "DELETE FROM jedi;",
+        "SAVEPOINT sp_1;",
+        "DELETE FROM jedi;",
+        "RELEASE SAVEPOINT sp_1;",
+        "SAVEPOINT sp_1;",
+        "ROLLBACK TO SAVEPOINT sp_1;",
+        "COMMIT;",
+    ].into_iter().map(|s| s.to_string()).collect::<Vec<String>>());
+}
+
+#[test]
+fn retry_on_serialization_failure() {
+
+    let jedi_table = TableDef::new("jedi");
+    let executor = MockExecutor::new().on_error("DELETE", "40001", 2);
+
+    let deleted = retry_transaction(&executor, &TransactionOptions::new(), 3, |trans| {
+        jedi_table.delete().all().query(trans)
+    });
+
+    assert_eq!(deleted.unwrap(), 0);
+    executor.assert_count(9);
+    executor.assert_sql(2, "ROLLBACK;");
+    executor.assert_sql(8, "COMMIT;");
+
+    let executor = MockExecutor::new().on_error("DELETE", "40P01", 5);
+    let deleted = retry_transaction(&executor, &TransactionOptions::new(), 2, |trans| {
+        jedi_table.delete().all().query(trans)
+    });
+
+    assert_eq!(deleted.unwrap_err().sqlstate(), Some("40P01"));
+    executor.assert_count(6);
+}
+
+#[test]
+fn mysql_isolation_statements() {
+
+    let executor = MockExecutor::with_adapter(sql::MysqlAdapter);
+    let options = TransactionOptions::new().isolation(IsolationLevel::Serializable);
+
+    transaction(&executor, &options, |_trans| Ok(())).unwrap();
+
+    executor.assert_count(3);
+    executor.assert_sql(0, "SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;");
+    executor.assert_sql(1, "START TRANSACTION;");
+    executor.assert_sql(2, "COMMIT;");
+}
+
+#[test]
+fn retry_with_mutable_state() {
+
+    let jedi_table = TableDef::new("jedi");
+    let executor = MockExecutor::new().on_error("DELETE", "40001", 2);
+
+    let mut attempts = 0;
+    let deleted = retry_transaction(&executor, &TransactionOptions::new(), 3, |trans| {
+        attempts += 1;
+        jedi_table.delete().all().query(trans)
+    });
+
+    assert_eq!(deleted.unwrap(), 0);
+    assert_eq!(attempts, 3);
+}
@@ -0,0 +1,79 @@
+use deuterium::*;
+
+#[test]
+fn truncate() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+
+    let query = jedi_table.truncate();
+    assert_sql!(query, "TRUNCATE jedi;");
+
+    let query = jedi_table.truncate().table(&padawan_table).only().restart_identity().cascade();
+    assert_sql!(query, "TRUNCATE ONLY jedi, padawan RESTART IDENTITY CASCADE;");
+
+    let query = jedi_table.truncate().continue_identity().restrict();
+    assert_sql!(query, "TRUNCATE jedi CONTINUE IDENTITY RESTRICT;");
+}
+
+#[test]
+fn truncate_sqlite() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+
+    let query = jedi_table.truncate().table(&padawan_table);
+    let mut ctx = SqlContext::new(Box::new(sql::SqliteAdapter));
+    assert_eq!(
+        query.try_to_final_statements(&mut ctx),
+        Ok(vec!["DELETE FROM jedi;".to_string(), "DELETE FROM padawan;".to_string()])
+    );
+
+    // Drivers run one statement per call, so a single string is rejected
+    assert!(query.try_to_final_sql(&mut ctx).is_err());
+    assert_eq!(jedi_table.truncate().try_to_final_sql(&mut ctx), Ok("DELETE FROM jedi;".to_string()));
+
+    let executor = MockExecutor::with_adapter(sql::SqliteAdapter).on_execute("DELETE", 2);
+    assert_eq!(execute_statements(&executor, &query).unwrap(), 4);
+    executor.assert_count(2);
+    executor.assert_sql(1, "DELETE FROM padawan;");
+
+    let query = jedi_table.truncate().cascade();
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::SqliteAdapter))).is_err());
+}
+
+#[test]
+fn truncate_mysql() {
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+
+    // One table per TRUNCATE
+    let query = jedi_table.truncate().table(&padawan_table);
+    assert_eq!(
+        query.try_to_final_statements(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        Ok(vec!["TRUNCATE jedi;".to_string(), "TRUNCATE padawan;".to_string()])
+    );
+
+    assert!(jedi_table.truncate().only().try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+    assert!(jedi_table.truncate().restart_identity().try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+    assert!(jedi_table.truncate().cascade().try_to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))).is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn truncate_sqlite_connection() {
+
+    let conn = ::rusqlite::Connection::open_in_memory().unwrap();
+    conn.execute_batch("CREATE TABLE jedi (name TEXT); CREATE TABLE padawan (name TEXT);
+                        INSERT INTO jedi VALUES ('Luke'); INSERT INTO padawan VALUES ('Rey'), ('Finn');").unwrap();
+
+    let jedi_table = TableDef::new("jedi");
+    let padawan_table = TableDef::new("padawan");
+
+    let query = jedi_table.truncate().table(&padawan_table);
+    assert_eq!(execute_statements(&conn, &query).unwrap(), 3);
+
+    let left: i64 = conn.query_row("SELECT COUNT(*) FROM padawan", &[], |row| row.get(0)).unwrap();
+    assert_eq!(left, 0);
+}
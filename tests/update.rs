@@ -50,4 +50,34 @@ fn update_returning() {
     let query = jedi_table.update().all().field(name.set(&"Luke".to_string())).returning_1(&name.qual());
     assert_sql!(query, "UPDATE jedi SET name = $1 RETURNING jedi.name;")
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn update_order_limit() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let side = NamedField::<bool>::field_of("side", &jedi_table);
+
+    let query = jedi_table.update().field(side.set(&true)).all().order_by(&id).limit(10);
+    assert_eq!(
+        query.to_final_sql(&mut SqlContext::new(Box::new(sql::MysqlAdapter))),
+        "UPDATE jedi SET side = ? ORDER BY id ASC LIMIT 10;".to_string()
+    );
+
+    assert_sql!(query, "UPDATE jedi SET side = $1 WHERE jedi.ctid IN (SELECT jedi.ctid FROM jedi ORDER BY id ASC LIMIT 10);");
+
+    // Rows of the joined table are chosen together with the updated ones
+    let orders_table = TableDef::new("orders");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &orders_table);
+
+    let query = jedi_table.update().field(side.set(&true)).from(&orders_table)
+        .where_(id.qual().is(jedi_id.qual()))
+        .order_by(&id).limit(10);
+    assert_sql!(query, "UPDATE jedi SET side = $1 FROM orders WHERE (jedi.ctid, orders.ctid) IN \
+                        (SELECT jedi.ctid, orders.ctid FROM jedi, orders WHERE jedi.id = orders.jedi_id ORDER BY id ASC LIMIT 10);");
+
+    let top = orders_table.select_1(&jedi_id).from_as("top");
+    let query = jedi_table.update().field(side.set(&true)).from(&top).all().order_by(&id).limit(10);
+    assert!(query.try_to_final_sql(&mut SqlContext::new(Box::new(sql::PostgreSqlAdapter))).is_err());
+}
@@ -0,0 +1,137 @@
+use deuterium::*;
+
+#[derive(Default)]
+struct Collector {
+    tables: Vec<String>,
+    fields: Vec<String>,
+    predicates: Vec<PredicateKind>,
+    values: Vec<Value>,
+    placeholders: Vec<u8>
+}
+
+impl Visitor for Collector {
+    fn visit_table(&mut self, table: &Table) {
+        self.tables.push(table.get_table_name().to_string());
+    }
+
+    fn visit_field(&mut self, field: &Field) {
+        self.fields.push(format!("{}.{}", field.table_name(), field.name()));
+    }
+
+    fn visit_predicate(&mut self, kind: PredicateKind) {
+        self.predicates.push(kind);
+    }
+
+    fn visit_value(&mut self, value: &Value) {
+        self.values.push(value.clone());
+    }
+
+    fn visit_placeholder(&mut self, placeholder: &Placeholder) {
+        self.placeholders.push(placeholder.get_idx());
+    }
+}
+
+fn collect(query: &Visitable) -> Collector {
+    let mut collector = Collector::default();
+    query.accept(&mut collector);
+    collector
+}
+
+#[test]
+fn visit_select() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let padawan_table = TableDef::new("padawan");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &padawan_table);
+    let force_level = NamedField::<i8>::field_of("force_level", &padawan_table);
+
+    let strong = padawan_table.select_1(&jedi_id).where_(force_level.gt(100i8));
+
+    let query = jedi_table.select_1(&name)
+        .inner_join(&padawan_table, jedi_id.is(id.clone()))
+        .where_(name.is(Placeholder::new(1)))
+        .and(id.in_(strong))
+        .order_by(&name);
+
+    let collector = collect(&query);
+    assert_eq!(collector.tables, vec!["jedi", "padawan", "padawan"]);
+    assert_eq!(collector.fields, vec![
+        "jedi.name", "padawan.jedi_id", "jedi.id", "jedi.name", "jedi.id",
+        "padawan.jedi_id", "padawan.force_level", "jedi.name"
+    ]);
+    assert_eq!(collector.predicates, vec![
+        PredicateKind::Is, PredicateKind::And, PredicateKind::Is, PredicateKind::In, PredicateKind::Inequality
+    ]);
+    assert_eq!(collector.values, vec![Value::I8(100)]);
+    assert_eq!(collector.placeholders, vec![1]);
+}
+
+#[test]
+fn visit_modify() {
+
+    let jedi_table = TableDef::new("jedi");
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+    let master_id = NamedField::<Option<i32>>::field_of("master_id", &jedi_table);
+
+    let query = jedi_table.update().field(name.set(&"Vader".to_string())).where_(master_id.is_null());
+    let collector = collect(&query);
+    assert_eq!(collector.tables, vec!["jedi"]);
+    assert_eq!(collector.fields, vec!["jedi.name", "jedi.master_id"]);
+    assert_eq!(collector.predicates, vec![PredicateKind::IsNull]);
+    assert_eq!(collector.values, vec![Value::String("Vader".to_string())]);
+
+    let query = jedi_table.delete().where_(name.is("Luke".to_string()).exclude());
+    let collector = collect(&query);
+    assert_eq!(collector.tables, vec!["jedi"]);
+    assert_eq!(collector.predicates, vec![PredicateKind::Exclude, PredicateKind::Is]);
+    assert_eq!(collector.values, vec![Value::String("Luke".to_string())]);
+
+    let mut query = jedi_table.insert_fields(&[&name]);
+    query.push_untyped(&["Luke".to_string().as_expr()]);
+    let collector = collect(&query);
+    assert_eq!(collector.tables, vec!["jedi"]);
+    assert_eq!(collector.fields, vec!["jedi.name"]);
+    assert_eq!(collector.values, vec![Value::String("Luke".to_string())]);
+}
+
+struct Published;
+
+impl Rewriter for Published {
+    fn rewrite_select(&mut self, query: &mut RewritableSelect) {
+        let published = RawPredicate::new("published").upcast();
+        let where_ = query.get_where_mut();
+        *where_ = Some(match where_.take() {
+            Some(predicate) => predicate.and(published),
+            None => published
+        });
+    }
+}
+
+#[test]
+fn rewrite_select() {
+
+    let jedi_table = TableDef::new("jedi");
+    let id = NamedField::<i32>::field_of("id", &jedi_table);
+    let name = NamedField::<String>::field_of("name", &jedi_table);
+
+    let padawan_table = TableDef::new("padawan");
+    let jedi_id = NamedField::<i32>::field_of("jedi_id", &padawan_table);
+
+    let original = jedi_table.select_1(&name).where_(id.in_(padawan_table.select_1(&jedi_id)));
+    let mut query = original.clone();
+    query.rewrite(&mut Published);
+    assert_sql!(original, "SELECT name FROM jedi WHERE id IN (SELECT jedi_id FROM padawan);");
+    assert_sql!(query, "SELECT name FROM jedi WHERE (id IN (SELECT jedi_id FROM padawan WHERE published)) AND (published);");
+
+    let mut query = jedi_table.select_all().from_as("j").select_all();
+    query.rewrite(&mut Published);
+    assert_sql!(query, "SELECT * FROM (SELECT * FROM jedi WHERE published) as j WHERE published;");
+
+    let mut query = jedi_table.update().field(name.set(&"Vader".to_string()))
+        .where_(id.in_(padawan_table.select_1(&jedi_id)));
+    query.rewrite(&mut Published);
+    assert_sql!(query, "UPDATE jedi SET name = $1 WHERE id IN (SELECT jedi_id FROM padawan WHERE published);");
+}